@@ -1,12 +1,17 @@
-use rkvm_input::writer::{Writer,WriterPlatform,WriterBuilderPlatform};
+use rkvm_input::event::Event;
+use rkvm_input::key::KeyEvent;
+use rkvm_input::sync::SyncEvent;
+use rkvm_input::writer::{Output, Writer, WriterBuilderPlatform, WriterPlatform};
 use rkvm_net::auth::{AuthChallenge, AuthStatus};
 use rkvm_net::message::Message;
 use rkvm_net::version::Version;
-use rkvm_net::{Pong, Update};
+use rkvm_net::{Feedback, Hello, Update};
 use std::collections::hash_map::Entry;
 use std::collections::HashMap;
+use std::future::{self, Future};
 use std::io;
-use std::time::Instant;
+use std::task::Poll;
+use std::time::{Duration, Instant};
 use thiserror::Error;
 use tokio::io::{AsyncWriteExt, BufStream};
 use tokio::net::TcpStream;
@@ -14,6 +19,9 @@ use tokio::time;
 use tokio_rustls::rustls::ServerName;
 use tokio_rustls::TlsConnector;
 
+use crate::focus::Focus;
+use crate::repeat::Watchdog;
+
 #[derive(Error, Debug)]
 pub enum Error {
     #[error("Network error: {0}")]
@@ -31,6 +39,9 @@ pub async fn run(
     port: u16,
     connector: &TlsConnector,
     password: &str,
+    name: &str,
+    focus: &Focus,
+    repeat_timeout: Duration,
 ) -> Result<(), Error> {
     // Intentionally don't impose any timeout for TCP connect.
     let stream = match hostname {
@@ -73,6 +84,19 @@ pub async fn run(
         });
     }
 
+    rkvm_net::timeout(rkvm_net::WRITE_TIMEOUT, async {
+        let hello = Hello {
+            name: name.to_owned(),
+        };
+
+        hello.encode(&mut stream).await?;
+        stream.flush().await?;
+
+        Ok(())
+    })
+    .await
+    .map_err(Error::Network)?;
+
     let challenge = rkvm_net::timeout(rkvm_net::READ_TIMEOUT, AuthChallenge::decode(&mut stream))
         .await
         .map_err(Error::Network)?;
@@ -99,17 +123,60 @@ pub async fn run(
 
     tracing::info!("Authenticated successfully");
 
+    // Reading is done separately, so that feedback can be written while waiting for updates.
+    let (reader, mut stream) = tokio::io::split(stream);
+    let mut read = Box::pin(rkvm_net::decode_owned::<Update, _>(reader));
+
     let mut start = Instant::now();
 
     let mut interval = time::interval(rkvm_net::PING_INTERVAL + rkvm_net::READ_TIMEOUT);
     let mut writers = HashMap::new();
+    let mut watchdog = Watchdog::new(repeat_timeout);
 
     // Interval ticks immediately after creation.
     interval.tick().await;
 
     loop {
         let update = tokio::select! {
-            update = Update::decode(&mut stream) => update.map_err(Error::Network)?,
+            (reader, update) = &mut read => {
+                read.set(rkvm_net::decode_owned(reader));
+                update.map_err(Error::Network)?
+            }
+            (id, result) = output(&mut writers) => {
+                let feedback = match result.map_err(Error::Input)? {
+                    Output::Led(event) => {
+                        tracing::debug!(id = %id, led = ?event.led, on = %event.on, "LED changed");
+                        Feedback::Led { id, event }
+                    }
+                    Output::Ff(event) => {
+                        tracing::trace!(id = %id, event = ?event, "Force feedback request");
+                        Feedback::Ff { id, event }
+                    }
+                };
+
+                rkvm_net::timeout(rkvm_net::WRITE_TIMEOUT, async {
+                    feedback.encode(&mut stream).await?;
+                    stream.flush().await?;
+
+                    Ok(())
+                })
+                .await
+                .map_err(Error::Network)?;
+
+                continue;
+            }
+            _ = sleep_until(watchdog.deadline()) => {
+                for (id, key) in watchdog.expired(Instant::now()) {
+                    tracing::warn!(id = %id, key = ?key, "Key wasn't repeated or released in time, releasing it");
+
+                    let writer = writers.get_mut(&id).unwrap();
+                    for event in [Event::Key(KeyEvent { key, down: false }), Event::Sync(SyncEvent::All)] {
+                        writer.write(&event).await.map_err(Error::Input)?;
+                    }
+                }
+
+                continue;
+            }
             _ = interval.tick() => return Err(Error::Network(io::Error::new(io::ErrorKind::TimedOut, "Ping timed out"))),
         };
 
@@ -123,8 +190,12 @@ pub async fn run(
                 rel,
                 abs,
                 keys,
+                leds,
+                ff,
+                raw,
                 delay,
                 period,
+                server_repeat,
             } => {
                 let entry = writers.entry(id);
                 if let Entry::Occupied(_) = entry {
@@ -134,6 +205,16 @@ pub async fn run(
                     )));
                 }
 
+                // The device must not repeat keys on its own when the server sends repeats.
+                let (delay, period) = match (server_repeat, delay, period) {
+                    (false, delay, period) => (delay, period),
+                    (true, Some(delay), Some(period)) if delay > 0 && period > 0 => {
+                        watchdog.insert(id, delay as _, period as _);
+                        (None, None)
+                    }
+                    (true, _, _) => (None, None),
+                };
+
                 let writer = async {
                     Writer::builder()?
                         .name(&name)
@@ -143,6 +224,9 @@ pub async fn run(
                         .rel(rel)?
                         .abs(abs)?
                         .key(keys)?
+                        .led(leds)?
+                        .ff(ff)?
+                        .raw(raw)?
                         .delay(delay)?
                         .period(period)?
                         .build()
@@ -163,6 +247,8 @@ pub async fn run(
                 );
             }
             Update::DestroyDevice { id } => {
+                watchdog.remove(id);
+
                 if writers.remove(&id).is_none() {
                     return Err(Error::Network(io::Error::new(
                         io::ErrorKind::InvalidData,
@@ -180,10 +266,19 @@ pub async fn run(
                     ))
                 })?;
 
+                if !watchdog.event(id, &event, Instant::now()) {
+                    tracing::trace!(id = %id, "Dropped a repeat of a released key");
+                    continue;
+                }
+
                 writer.write(&event).await.map_err(Error::Input)?;
 
                 tracing::trace!(id = %id, "Wrote an event to device");
             }
+            Update::Focus { active } => {
+                tracing::info!(active = %active, "Focus changed");
+                focus.changed(active).await;
+            }
             Update::Ping => {
                 let duration = start.elapsed();
                 tracing::debug!(duration = ?duration, "Received ping");
@@ -192,7 +287,7 @@ pub async fn run(
                 interval.reset();
 
                 rkvm_net::timeout(rkvm_net::WRITE_TIMEOUT, async {
-                    Pong.encode(&mut stream).await?;
+                    Feedback::Pong.encode(&mut stream).await?;
                     stream.flush().await?;
 
                     Ok(())
@@ -206,3 +301,33 @@ pub async fn run(
         }
     }
 }
+
+// Sleeps until the given time, forever if there's none.
+async fn sleep_until(deadline: Option<Instant>) {
+    match deadline {
+        Some(deadline) => time::sleep_until(deadline.into()).await,
+        None => future::pending().await,
+    }
+}
+
+// Waits for the system to write to any of the devices.
+async fn output(writers: &mut HashMap<usize, Writer>) -> (usize, Result<Output, io::Error>) {
+    let mut reads = writers
+        .iter_mut()
+        .map(|(id, writer)| {
+            let id = *id;
+            Box::pin(async move { (id, writer.read().await) })
+        })
+        .collect::<Vec<_>>();
+
+    future::poll_fn(|cx| {
+        for read in &mut reads {
+            if let Poll::Ready(result) = read.as_mut().poll(cx) {
+                return Poll::Ready(result);
+            }
+        }
+
+        Poll::Pending
+    })
+    .await
+}
@@ -13,6 +13,10 @@ pub struct Config {
     pub certificate: PathBuf,
     pub password: String,
     pub reconnect_delay: Option<u64>,
+    pub name: Option<String>,
+    pub on_focus_change: Option<String>,
+    pub focus_state_file: Option<PathBuf>,
+    pub repeat_timeout: Option<u64>,
 }
 
 pub struct Server {
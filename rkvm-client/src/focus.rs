@@ -0,0 +1,115 @@
+use crate::config::Config;
+use std::path::PathBuf;
+use tokio::fs;
+use tokio::process::Command;
+use tokio::task::JoinHandle;
+
+/// Reacts to the server telling the client whether it's the current target.
+pub struct Focus {
+    command: Option<String>,
+    state_file: Option<PathBuf>,
+}
+
+impl Focus {
+    pub fn new(config: &Config) -> Self {
+        Self {
+            command: config.on_focus_change.clone(),
+            state_file: config.focus_state_file.clone(),
+        }
+    }
+
+    /// Writes the state file and starts the command, failures are only logged.
+    pub async fn changed(&self, active: bool) {
+        if let Some(path) = &self.state_file {
+            if let Err(err) = fs::write(path, state(active)).await {
+                tracing::warn!("Error writing focus state file {}: {}", path.display(), err);
+            }
+        }
+
+        if let Some(command) = &self.command {
+            run(command, active);
+        }
+    }
+}
+
+fn state(active: bool) -> &'static str {
+    if active {
+        "active\n"
+    } else {
+        "inactive\n"
+    }
+}
+
+fn active_env(active: bool) -> (&'static str, &'static str) {
+    ("RKVM_ACTIVE", if active { "1" } else { "0" })
+}
+
+// Returns the task waiting for the command, so that tests can wait for it too.
+fn run(command: &str, active: bool) -> Option<JoinHandle<()>> {
+    #[cfg(not(target_os = "windows"))]
+    let mut command = {
+        let mut shell = Command::new("sh");
+        shell.arg("-c").arg(command);
+        shell
+    };
+
+    #[cfg(target_os = "windows")]
+    let mut command = {
+        let mut shell = Command::new("cmd");
+        shell.arg("/C").arg(command);
+        shell
+    };
+
+    let (key, value) = active_env(active);
+    let child = command.env(key, value).spawn();
+
+    let mut child = match child {
+        Ok(child) => child,
+        Err(err) => {
+            tracing::error!("Error running focus command: {}", err);
+            return None;
+        }
+    };
+
+    // Don't hold up updates from the server.
+    let task = tokio::spawn(async move {
+        match child.wait().await {
+            Ok(status) if status.success() => {}
+            Ok(status) => tracing::warn!("Focus command failed: {}", status),
+            Err(err) => tracing::error!("Error waiting for focus command: {}", err),
+        }
+    });
+
+    Some(task)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[cfg(not(target_os = "windows"))]
+    #[tokio::test]
+    async fn hooks_receive_focus_state() {
+        let dir = std::env::temp_dir().join(format!("rkvm-focus-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let env = dir.join("env");
+        let command = format!("echo \"$RKVM_ACTIVE\" >> '{}'", env.display());
+        let focus = Focus {
+            command: None,
+            state_file: Some(dir.join("state")),
+        };
+
+        for active in [true, false] {
+            focus.changed(active).await;
+            run(&command, active).unwrap().await.unwrap();
+        }
+
+        let state = std::fs::read_to_string(dir.join("state")).unwrap();
+        let env = std::fs::read_to_string(env).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(state, "inactive\n");
+        assert_eq!(env, "1\n0\n");
+    }
+}
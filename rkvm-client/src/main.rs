@@ -1,10 +1,13 @@
 ﻿#![cfg_attr(target_os = "windows", windows_subsystem = "windows")]
 mod client;
 mod config;
+mod focus;
+mod repeat;
 mod tls;
 
 use clap::Parser;
 use config::Config;
+use focus::Focus;
 use std::time::Duration;
 use std::path::PathBuf;
 use std::fs::OpenOptions;
@@ -28,9 +31,16 @@ struct Args {
     log_file: Option<PathBuf>,
 }
 
-async fn main_loop(config: &Config, connector: &TlsConnector) -> ExitCode {
-     tokio::select! {
-        result = client::run(&config.server.hostname, config.server.port, connector, &config.password) => {
+async fn main_loop(
+    config: &Config,
+    connector: &TlsConnector,
+    name: &str,
+    focus: &Focus,
+) -> ExitCode {
+    let repeat_timeout = Duration::from_millis(config.repeat_timeout.unwrap_or(250));
+
+    tokio::select! {
+        result = client::run(&config.server.hostname, config.server.port, connector, &config.password, name, focus, repeat_timeout) => {
             if let Err(err) = result {
                 tracing::error!("Error: {}", err);
                 return ExitCode::FAILURE;
@@ -85,6 +95,17 @@ async fn main() -> ExitCode {
         }
     };
 
+    let name = match &config.name {
+        Some(name) => name.clone(),
+        None => match hostname::get() {
+            Ok(name) => name.to_string_lossy().into_owned(),
+            Err(err) => {
+                tracing::error!("Error getting hostname: {}", err);
+                return ExitCode::FAILURE;
+            }
+        },
+    };
+
     let connector = match tls::configure(&config.certificate).await {
         Ok(connector) => connector,
         Err(err) => {
@@ -93,11 +114,13 @@ async fn main() -> ExitCode {
         }
     };
     
+    let focus = Focus::new(&config);
+
     match config.reconnect_delay.map(Duration::from_secs) {
-        None => main_loop(&config, &connector).await,
+        None => main_loop(&config, &connector, &name, &focus).await,
         Some(reconnect_delay) => {
             loop {
-                let code = main_loop(&config, &connector).await;
+                let code = main_loop(&config, &connector, &name, &focus).await;
                 if code == ExitCode::SUCCESS {
                     return code;
                 }
@@ -0,0 +1,138 @@
+use rkvm_input::event::Event;
+use rkvm_input::key::{Key, KeyEvent};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// Releases keys the server stopped repeating without their release arriving in time,
+/// used when the server sends key repeats instead of the devices repeating keys on their own.
+///
+/// Like the kernel, the server only repeats the last key pressed on a device and stops on any release,
+/// so that's the only key watched.
+pub struct Watchdog {
+    timeout: Duration,
+    devices: HashMap<usize, Device>,
+}
+
+struct Device {
+    delay: Duration,
+    period: Duration,
+    // The key expected to repeat, along with the time its next repeat or release is due by.
+    held: Option<(Key, Instant)>,
+}
+
+impl Watchdog {
+    pub fn new(timeout: Duration) -> Self {
+        Self {
+            timeout,
+            devices: HashMap::new(),
+        }
+    }
+
+    /// Starts watching a device repeating keys with the given delay and period in milliseconds.
+    pub fn insert(&mut self, id: usize, delay: u64, period: u64) {
+        self.devices.insert(
+            id,
+            Device {
+                delay: Duration::from_millis(delay),
+                period: Duration::from_millis(period),
+                held: None,
+            },
+        );
+    }
+
+    pub fn remove(&mut self, id: usize) {
+        self.devices.remove(&id);
+    }
+
+    /// Accounts for an event sent by the server, returning whether it should be written.
+    ///
+    /// Repeats of keys that were already released are dropped.
+    pub fn event(&mut self, id: usize, event: &Event, now: Instant) -> bool {
+        let device = match self.devices.get_mut(&id) {
+            Some(device) => device,
+            None => return true,
+        };
+
+        match *event {
+            Event::Key(KeyEvent { key, down: true }) => {
+                device.held = Some((key, now + device.delay + self.timeout));
+            }
+            Event::Key(KeyEvent { down: false, .. }) => device.held = None,
+            Event::Repeat(key) => match &mut device.held {
+                Some((held, deadline)) if *held == key => {
+                    *deadline = now + device.period + self.timeout;
+                }
+                _ => return false,
+            },
+            _ => {}
+        }
+
+        true
+    }
+
+    /// Returns the earliest time a key will have to be released by, if any is held.
+    pub fn deadline(&self) -> Option<Instant> {
+        self.devices
+            .values()
+            .filter_map(|device| device.held.map(|(_, deadline)| deadline))
+            .min()
+    }
+
+    /// Returns the keys that weren't repeated or released in time, forgetting them.
+    pub fn expired(&mut self, now: Instant) -> Vec<(usize, Key)> {
+        let mut expired = Vec::new();
+
+        for (id, device) in &mut self.devices {
+            if let Some((key, deadline)) = device.held {
+                if deadline <= now {
+                    device.held = None;
+                    expired.push((*id, key));
+                }
+            }
+        }
+
+        expired
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rkvm_input::key::Keyboard;
+
+    #[test]
+    fn releases_keys_not_repeated_in_time() {
+        let mut watchdog = Watchdog::new(Duration::from_millis(100));
+        watchdog.insert(0, 250, 30);
+
+        let key = |key, down| {
+            Event::Key(KeyEvent {
+                key: Key::Key(key),
+                down,
+            })
+        };
+        let repeat = |key| Event::Repeat(Key::Key(key));
+        let start = Instant::now();
+        let at = |ms| start + Duration::from_millis(ms);
+
+        assert!(watchdog.event(0, &key(Keyboard::LeftShift, true), start));
+        assert!(watchdog.event(0, &key(Keyboard::A, true), start));
+        assert_eq!(watchdog.deadline(), Some(at(350)));
+
+        // Only the last pressed key is watched.
+        assert!(!watchdog.event(0, &repeat(Keyboard::LeftShift), start));
+        assert!(watchdog.event(0, &repeat(Keyboard::A), start));
+        assert_eq!(watchdog.deadline(), Some(at(130)));
+
+        assert!(watchdog.expired(at(0)).is_empty());
+        let expired = watchdog.expired(at(130));
+        assert_eq!(expired, [(0, Key::Key(Keyboard::A))]);
+        assert_eq!(watchdog.deadline(), None);
+
+        // A late repeat of the released key is dropped.
+        assert!(!watchdog.event(0, &repeat(Keyboard::A), at(200)));
+
+        // Devices that aren't watched repeat keys on their own.
+        assert!(watchdog.event(1, &repeat(Keyboard::A), start));
+    }
+}
@@ -0,0 +1,122 @@
+use clap::{Parser, Subcommand};
+use rkvm_net::control::{Request, Response, Status, Target};
+use rkvm_net::message::Message;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use thiserror::Error;
+use tokio::io::{AsyncWriteExt, BufStream};
+use tokio::net::UnixStream;
+
+#[derive(Parser)]
+#[clap(name = "rkvm-ctl", about = "A tool to control a running rkvm server")]
+struct Args {
+    #[clap(
+        long,
+        short,
+        help = "Path to the server's control socket",
+        default_value = "/run/rkvm-server.sock"
+    )]
+    socket: PathBuf,
+    #[clap(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    #[clap(about = "Switch to a client by name or index, or to the server using \"server\" or 0")]
+    Switch { target: String },
+    #[clap(about = "Switch to the next connected client")]
+    Cycle,
+    #[clap(about = "List clients and devices")]
+    Status,
+}
+
+#[derive(Error, Debug)]
+enum Error {
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    #[error("Unexpected response from server")]
+    Response,
+    #[error("{0}")]
+    Server(String),
+}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() -> ExitCode {
+    let args = Args::parse();
+
+    let request = match args.command {
+        Command::Switch { target } => Request::Switch(parse_target(target)),
+        Command::Cycle => Request::Cycle,
+        Command::Status => Request::Status,
+    };
+
+    match run(&args.socket, request).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("Error: {}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn parse_target(target: String) -> Target {
+    if target == "server" {
+        return Target::Server;
+    }
+
+    match target.parse() {
+        Ok(idx) => Target::Index(idx),
+        Err(_) => Target::Name(target),
+    }
+}
+
+async fn run(socket: &Path, request: Request) -> Result<(), Error> {
+    let stream = UnixStream::connect(socket).await?;
+    let mut stream = BufStream::new(stream);
+
+    request.encode(&mut stream).await?;
+    stream.flush().await?;
+
+    match (request, Response::decode(&mut stream).await?) {
+        (_, Response::Error(err)) => Err(Error::Server(err)),
+        (Request::Status, Response::Status(status)) => {
+            print_status(&status);
+            Ok(())
+        }
+        (Request::Switch(_) | Request::Cycle, Response::Done) => Ok(()),
+        _ => Err(Error::Response),
+    }
+}
+
+fn print_status(status: &Status) {
+    let current = |idx| if idx == status.current { "*" } else { " " };
+
+    println!("Clients:");
+    println!("{} 0 server", current(0));
+
+    for client in &status.clients {
+        let name = client.name.as_deref().unwrap_or("-");
+        let addr = match client.addr {
+            Some(addr) => addr.to_string(),
+            None => "not connected".to_owned(),
+        };
+
+        println!("{} {} {} ({})", current(client.idx), client.idx, name, addr);
+    }
+
+    println!("Devices:");
+
+    for device in &status.devices {
+        print!(
+            "  {} {:?} {:04x}:{:04x}",
+            device.id, device.name, device.vendor, device.product
+        );
+
+        match device.pinned {
+            Some(idx) => println!(" (pinned to {})", idx),
+            None => println!(),
+        }
+    }
+}
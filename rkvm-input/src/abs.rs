@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 pub enum AbsEvent {
     Axis { axis: AbsAxis, value: i32 },
     MtToolType { value: ToolType },
@@ -61,7 +61,7 @@ pub struct AbsInfo {
     pub resolution: i32,
 }
 
-#[derive(Clone, Copy, Serialize, Deserialize, Debug)]
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize, Debug)]
 pub enum ToolType {
     Finger,
     Pen,
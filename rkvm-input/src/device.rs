@@ -1,38 +1,99 @@
-use serde::Deserialize;
+use crate::abs::AbsAxis;
+use crate::key::{Button, Key, Keyboard};
+use crate::rel::RelAxis;
+
+use regex::Regex;
+use serde::{Deserialize, Deserializer};
+use std::collections::HashSet;
+use std::ffi::{CStr, CString};
 
 /// Describes parts of a device
 #[derive(Deserialize, Clone)]
 #[serde(rename_all = "kebab-case")]
 pub struct DeviceSpec {
-    pub name: Option<std::ffi::CString>,
+    pub name: Option<CString>,
+    /// Shell-style pattern the name has to match, `*` matches any text and `?` any single character.
+    #[serde(default, deserialize_with = "deserialize_glob")]
+    pub name_glob: Option<Pattern>,
+    /// Regular expression the name has to match, anywhere unless anchored.
+    #[serde(default, deserialize_with = "deserialize_regex")]
+    pub name_regex: Option<Pattern>,
     pub vendor_id: Option<u16>,
     pub product_id: Option<u16>,
+    pub bus: Option<Bus>,
+    /// Shell-style pattern the physical path (such as `usb-0000:00:14.0-1/input0`) has to match.
+    #[serde(default, deserialize_with = "deserialize_glob")]
+    pub phys: Option<Pattern>,
+    /// Shell-style pattern the unique identifier (usually a serial number) has to match.
+    #[serde(default, deserialize_with = "deserialize_glob")]
+    pub uniq: Option<Pattern>,
+    pub class: Option<DeviceClass>,
 }
 
 impl DeviceSpec {
-    /// Compares the given values to this DeviceSpec
+    /// Compares the given device to this DeviceSpec
     ///
     /// A None value means we skip that comparison
-    pub fn matches(
-        &self,
-        other_name: &std::ffi::CStr,
-        other_vendor_id: &u16,
-        other_product_id: &u16,
-    ) -> bool {
+    pub fn matches(&self, device: &DeviceInfo) -> bool {
         if let Some(name) = &self.name {
-            if name.as_c_str() != other_name {
+            if name != &device.name {
+                return false;
+            }
+        }
+
+        if let Some(name) = &self.name_glob {
+            if !name.matches(&device.name) {
+                return false;
+            }
+        }
+
+        if let Some(name) = &self.name_regex {
+            if !name.matches(&device.name) {
                 return false;
             }
         }
 
         if let Some(vendor_id) = &self.vendor_id {
-            if vendor_id != other_vendor_id {
+            if *vendor_id != device.vendor {
                 return false;
             }
         }
 
         if let Some(product_id) = &self.product_id {
-            if product_id != other_product_id {
+            if *product_id != device.product {
+                return false;
+            }
+        }
+
+        if let Some(bus) = &self.bus {
+            if Some(*bus) != device.bus {
+                return false;
+            }
+        }
+
+        // Devices without a physical path or unique identifier never match a pattern for it.
+        if let Some(phys) = &self.phys {
+            if !device
+                .phys
+                .as_deref()
+                .is_some_and(|other| phys.matches(other))
+            {
+                return false;
+            }
+        }
+
+        if let Some(uniq) = &self.uniq {
+            if !device
+                .uniq
+                .as_deref()
+                .is_some_and(|other| uniq.matches(other))
+            {
+                return false;
+            }
+        }
+
+        if let Some(class) = &self.class {
+            if !device.classes.contains(class) {
                 return false;
             }
         }
@@ -40,3 +101,150 @@ impl DeviceSpec {
         true
     }
 }
+
+/// A compiled name, phys or uniq pattern.
+#[derive(Clone, Debug)]
+pub struct Pattern(Regex);
+
+impl Pattern {
+    pub fn glob(pattern: &str) -> Result<Self, regex::Error> {
+        let mut regex = String::from("^");
+
+        for c in pattern.chars() {
+            match c {
+                '*' => regex.push_str(".*"),
+                '?' => regex.push('.'),
+                c => regex.push_str(&regex::escape(c.encode_utf8(&mut [0; 4]))),
+            }
+        }
+
+        regex.push('$');
+        Regex::new(&regex).map(Self)
+    }
+
+    pub fn regex(pattern: &str) -> Result<Self, regex::Error> {
+        Regex::new(pattern).map(Self)
+    }
+
+    pub fn matches(&self, value: &CStr) -> bool {
+        self.0.is_match(&value.to_string_lossy())
+    }
+}
+
+fn deserialize_glob<'de, D>(deserializer: D) -> Result<Option<Pattern>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let pattern = String::deserialize(deserializer)?;
+    Pattern::glob(&pattern)
+        .map(Some)
+        .map_err(serde::de::Error::custom)
+}
+
+fn deserialize_regex<'de, D>(deserializer: D) -> Result<Option<Pattern>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let pattern = String::deserialize(deserializer)?;
+    Pattern::regex(&pattern)
+        .map(Some)
+        .map_err(serde::de::Error::custom)
+}
+
+/// The bus a device is connected through.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Bus {
+    Pci,
+    Isapnp,
+    Usb,
+    Hil,
+    Bluetooth,
+    Virtual,
+    Isa,
+    I8042,
+    Xtkbd,
+    Rs232,
+    Gameport,
+    Parport,
+    Amiga,
+    Adb,
+    I2c,
+    Host,
+    Gsc,
+    Atari,
+    Spi,
+    Rmi,
+    Cec,
+}
+
+/// Kind of a device, derived from its capabilities.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DeviceClass {
+    Keyboard,
+    /// Mice, trackballs, drawing tablets and touchscreens.
+    Pointer,
+    /// Gamepads and joysticks.
+    Gamepad,
+    Touchpad,
+}
+
+impl DeviceClass {
+    /// Classifies a device by its capabilities, a device can be in several classes or none at all.
+    pub fn classify(
+        rel: &HashSet<RelAxis>,
+        abs: &HashSet<AbsAxis>,
+        keys: &HashSet<Key>,
+    ) -> HashSet<Self> {
+        let key = |key| keys.contains(&Key::Key(key));
+        let button = |button| keys.contains(&Key::Button(button));
+
+        let mut classes = HashSet::new();
+
+        // Power buttons and media remotes report some keys too, but not letters.
+        if key(Keyboard::A) && key(Keyboard::Z) && key(Keyboard::Space) {
+            classes.insert(Self::Keyboard);
+        }
+
+        if button(Button::South) || button(Button::Trigger) {
+            classes.insert(Self::Gamepad);
+        }
+
+        let abs_xy = abs.contains(&AbsAxis::X) && abs.contains(&AbsAxis::Y);
+        let touchpad = abs_xy && button(Button::ToolFinger) && !button(Button::ToolPen);
+
+        if touchpad {
+            classes.insert(Self::Touchpad);
+        } else if (rel.contains(&RelAxis::X) && rel.contains(&RelAxis::Y))
+            || (abs_xy
+                && (button(Button::Left) || button(Button::Touch) || button(Button::ToolPen)))
+        {
+            classes.insert(Self::Pointer);
+        }
+
+        classes
+    }
+}
+
+/// What is known about a device when matching it against a [`DeviceSpec`].
+#[derive(Clone, Debug, Default)]
+pub struct DeviceInfo {
+    pub name: CString,
+    pub vendor: u16,
+    pub product: u16,
+    /// None if the bus is not known to rkvm.
+    pub bus: Option<Bus>,
+    pub phys: Option<CString>,
+    pub uniq: Option<CString>,
+    pub classes: HashSet<DeviceClass>,
+}
+
+impl DeviceInfo {
+    /// Returns whether a device should be forwarded: it has to match an entry of the allowlist (if there's any)
+    /// and none of the denylist.
+    pub fn allowed(&self, allowlist: &[DeviceSpec], denylist: &[DeviceSpec]) -> bool {
+        (allowlist.is_empty() || allowlist.iter().any(|spec| spec.matches(self)))
+            && !denylist.iter().any(|spec| spec.matches(self))
+    }
+}
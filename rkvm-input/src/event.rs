@@ -1,14 +1,18 @@
 use crate::abs::AbsEvent;
-use crate::key::KeyEvent;
+use crate::key::{Key, KeyEvent};
+use crate::raw::RawEvent;
 use crate::rel::RelEvent;
 use crate::sync::SyncEvent;
 
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 pub enum Event {
     Rel(RelEvent),
     Abs(AbsEvent),
     Key(KeyEvent),
+    /// Autorepeat of a held key.
+    Repeat(Key),
     Sync(SyncEvent),
+    Raw(RawEvent),
 }
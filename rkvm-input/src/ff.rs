@@ -0,0 +1,118 @@
+use serde::{Deserialize, Serialize};
+
+/// A force feedback capability of a device, either an effect type, a waveform or a setting.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum Ff {
+    Rumble,
+    Periodic,
+    Constant,
+    Spring,
+    Friction,
+    Damper,
+    Inertia,
+    Ramp,
+    Square,
+    Triangle,
+    Sine,
+    SawUp,
+    SawDown,
+    Gain,
+    Autocenter,
+}
+
+/// A force feedback request made by the system to a device.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum FfEvent {
+    /// Uploads a new effect, or updates the one with the same ID.
+    Upload(FfEffect),
+    Erase {
+        id: i16,
+    },
+    /// Plays an effect the given number of times, zero stops it.
+    Play {
+        id: i16,
+        count: i32,
+    },
+    Gain(u16),
+    Autocenter(u16),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FfEffect {
+    pub id: i16,
+    pub direction: u16,
+    pub trigger: FfTrigger,
+    pub replay: FfReplay,
+    pub kind: FfKind,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FfTrigger {
+    // Raw key code, devices with trigger buttons are rare and both ends are Linux anyway.
+    pub button: u16,
+    pub interval: u16,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FfReplay {
+    pub length: u16,
+    pub delay: u16,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum FfKind {
+    Rumble {
+        strong_magnitude: u16,
+        weak_magnitude: u16,
+    },
+    // Custom waveforms are not supported.
+    Periodic {
+        waveform: FfWaveform,
+        period: u16,
+        magnitude: i16,
+        offset: i16,
+        phase: u16,
+        envelope: FfEnvelope,
+    },
+    Constant {
+        level: i16,
+        envelope: FfEnvelope,
+    },
+    Ramp {
+        start_level: i16,
+        end_level: i16,
+        envelope: FfEnvelope,
+    },
+    // Conditions for the X and Y axis.
+    Spring([FfCondition; 2]),
+    Friction([FfCondition; 2]),
+    Damper([FfCondition; 2]),
+    Inertia([FfCondition; 2]),
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum FfWaveform {
+    Square,
+    Triangle,
+    Sine,
+    SawUp,
+    SawDown,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FfEnvelope {
+    pub attack_length: u16,
+    pub attack_level: u16,
+    pub fade_length: u16,
+    pub fade_level: u16,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FfCondition {
+    pub right_saturation: u16,
+    pub left_saturation: u16,
+    pub right_coeff: i16,
+    pub left_coeff: i16,
+    pub deadband: u16,
+    pub center: i16,
+}
@@ -1,6 +1,9 @@
 use crate::abs::{AbsAxis, AbsInfo};
+use crate::device::DeviceInfo;
 use crate::event::Event;
+use crate::ff::{Ff, FfEffect};
 use crate::key::Key;
+use crate::led::{Led, LedEvent};
 use crate::rel::RelAxis;
 
 
@@ -25,6 +28,11 @@ pub trait InterceptorPlatform: Sized {
 
     fn version(&self) -> u16;
 
+    /// Describes the device as it was before rkvm opened it, for matching against a [`DeviceSpec`].
+    ///
+    /// [`DeviceSpec`]: crate::device::DeviceSpec
+    fn info(&self) -> &DeviceInfo;
+
     fn rel(&self) ->  HashSet<RelAxis>;
 
     fn abs(&self) -> HashMap<AbsAxis, AbsInfo>;
@@ -32,4 +40,26 @@ pub trait InterceptorPlatform: Sized {
     fn key(&self) -> HashSet<Key>;
 
     fn repeat(&self) -> Repeat;
+
+    /// Returns the codes of events the device reports as raw events, as pairs of type and code.
+    fn raw(&self) -> HashSet<(u16, u16)>;
+
+    /// Returns the LEDs the device has, along with their current state.
+    fn leds(&self) -> HashMap<Led, bool>;
+
+    fn set_led(&mut self, event: LedEvent) -> Result<(), Error>;
+
+    fn ff(&self) -> HashSet<Ff>;
+
+    /// Uploads an effect and returns its ID on the device, an ID of -1 creates a new one.
+    fn upload_ff(&mut self, effect: FfEffect) -> Result<i16, Error>;
+
+    fn erase_ff(&mut self, id: i16) -> Result<(), Error>;
+
+    /// Plays an effect the given number of times, zero stops it.
+    fn play_ff(&mut self, id: i16, count: i32) -> Result<(), Error>;
+
+    fn set_ff_gain(&mut self, gain: u16) -> Result<(), Error>;
+
+    fn set_ff_autocenter(&mut self, autocenter: u16) -> Result<(), Error>;
 }
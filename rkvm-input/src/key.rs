@@ -16,5 +16,7 @@ pub struct KeyEvent {
 pub enum Key {
     Key(Keyboard),
     Button(Button),
+    /// A Linux key code without a name in [`Keyboard`] or [`Button`].
+    Raw(u16),
 }
 
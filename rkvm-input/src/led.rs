@@ -0,0 +1,22 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct LedEvent {
+    pub led: Led,
+    pub on: bool,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum Led {
+    NumLock,
+    CapsLock,
+    ScrollLock,
+    Compose,
+    Kana,
+    Sleep,
+    Suspend,
+    Mute,
+    Misc,
+    Mail,
+    Charging,
+}
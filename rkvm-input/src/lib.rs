@@ -1,9 +1,12 @@
 pub mod abs;
 pub mod device;
 pub mod event;
+pub mod ff;
 pub mod interceptor;
 pub mod key;
+pub mod led;
 pub mod monitor;
+pub mod raw;
 pub mod rel;
 pub mod sync;
 pub mod writer;
@@ -0,0 +1,65 @@
+use crate::convert::Convert;
+use crate::linux::glue;
+
+use crate::device::Bus;
+
+impl Convert for Bus {
+    type Raw = u16;
+
+    fn from_raw(code: Self::Raw) -> Option<Self> {
+        let bus = match code as _ {
+            glue::BUS_PCI => Self::Pci,
+            glue::BUS_ISAPNP => Self::Isapnp,
+            glue::BUS_USB => Self::Usb,
+            glue::BUS_HIL => Self::Hil,
+            glue::BUS_BLUETOOTH => Self::Bluetooth,
+            glue::BUS_VIRTUAL => Self::Virtual,
+            glue::BUS_ISA => Self::Isa,
+            glue::BUS_I8042 => Self::I8042,
+            glue::BUS_XTKBD => Self::Xtkbd,
+            glue::BUS_RS232 => Self::Rs232,
+            glue::BUS_GAMEPORT => Self::Gameport,
+            glue::BUS_PARPORT => Self::Parport,
+            glue::BUS_AMIGA => Self::Amiga,
+            glue::BUS_ADB => Self::Adb,
+            glue::BUS_I2C => Self::I2c,
+            glue::BUS_HOST => Self::Host,
+            glue::BUS_GSC => Self::Gsc,
+            glue::BUS_ATARI => Self::Atari,
+            glue::BUS_SPI => Self::Spi,
+            glue::BUS_RMI => Self::Rmi,
+            glue::BUS_CEC => Self::Cec,
+            _ => return None,
+        };
+
+        Some(bus)
+    }
+
+    fn to_raw(&self) -> Option<Self::Raw> {
+        let code = match self {
+            Self::Pci => glue::BUS_PCI,
+            Self::Isapnp => glue::BUS_ISAPNP,
+            Self::Usb => glue::BUS_USB,
+            Self::Hil => glue::BUS_HIL,
+            Self::Bluetooth => glue::BUS_BLUETOOTH,
+            Self::Virtual => glue::BUS_VIRTUAL,
+            Self::Isa => glue::BUS_ISA,
+            Self::I8042 => glue::BUS_I8042,
+            Self::Xtkbd => glue::BUS_XTKBD,
+            Self::Rs232 => glue::BUS_RS232,
+            Self::Gameport => glue::BUS_GAMEPORT,
+            Self::Parport => glue::BUS_PARPORT,
+            Self::Amiga => glue::BUS_AMIGA,
+            Self::Adb => glue::BUS_ADB,
+            Self::I2c => glue::BUS_I2C,
+            Self::Host => glue::BUS_HOST,
+            Self::Gsc => glue::BUS_GSC,
+            Self::Atari => glue::BUS_ATARI,
+            Self::Spi => glue::BUS_SPI,
+            Self::Rmi => glue::BUS_RMI,
+            Self::Cec => glue::BUS_CEC,
+        };
+
+        Some(code as _)
+    }
+}
@@ -2,7 +2,7 @@ use crate::linux::glue::{self, libevdev};
 
 use std::ffi::CStr;
 use std::fs::File;
-use std::io::{Error, ErrorKind};
+use std::io::Error;
 use std::mem::MaybeUninit;
 use std::os::fd::AsRawFd;
 use std::path::Path;
@@ -19,7 +19,7 @@ impl Evdev {
     pub fn new() -> Result<Self, Error> {
         let evdev = unsafe { glue::libevdev_new() };
         let evdev = NonNull::new(evdev)
-            .ok_or_else(|| Error::new(ErrorKind::Other, "Failed to create device"))?;
+            .ok_or_else(|| Error::other("Failed to create device"))?;
 
         Ok(Self { evdev, file: None })
     }
@@ -40,7 +40,7 @@ impl Evdev {
 
         let ret = unsafe { glue::libevdev_new_from_fd(file.as_raw_fd(), evdev.as_mut_ptr()) };
         if ret < 0 {
-            return Err(Error::from_raw_os_error(-ret).into());
+            return Err(Error::from_raw_os_error(-ret));
         }
 
         let evdev = unsafe { evdev.assume_init() };
@@ -71,6 +71,28 @@ impl Evdev {
         unsafe { glue::libevdev_get_id_version(self.as_ptr()) as _ }
     }
 
+    pub fn bustype(&self) -> u16 {
+        unsafe { glue::libevdev_get_id_bustype(self.as_ptr()) as _ }
+    }
+
+    pub fn phys(&self) -> Option<&CStr> {
+        let phys = unsafe { glue::libevdev_get_phys(self.as_ptr()) };
+        if phys.is_null() {
+            return None;
+        }
+
+        Some(unsafe { CStr::from_ptr(phys) })
+    }
+
+    pub fn uniq(&self) -> Option<&CStr> {
+        let uniq = unsafe { glue::libevdev_get_uniq(self.as_ptr()) };
+        if uniq.is_null() {
+            return None;
+        }
+
+        Some(unsafe { CStr::from_ptr(uniq) })
+    }
+
     pub fn file(&self) -> Option<&AsyncFd<File>> {
         self.file.as_ref()
     }
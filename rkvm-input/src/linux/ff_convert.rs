@@ -0,0 +1,348 @@
+use crate::convert::Convert;
+use crate::linux::glue;
+
+use crate::ff::{Ff, FfCondition, FfEffect, FfEnvelope, FfKind, FfReplay, FfTrigger, FfWaveform};
+
+use std::mem;
+
+impl Convert for Ff {
+    type Raw = u16;
+
+    fn from_raw(code: Self::Raw) -> Option<Self> {
+        let ff = match code as _ {
+            glue::FF_RUMBLE => Self::Rumble,
+            glue::FF_PERIODIC => Self::Periodic,
+            glue::FF_CONSTANT => Self::Constant,
+            glue::FF_SPRING => Self::Spring,
+            glue::FF_FRICTION => Self::Friction,
+            glue::FF_DAMPER => Self::Damper,
+            glue::FF_INERTIA => Self::Inertia,
+            glue::FF_RAMP => Self::Ramp,
+            glue::FF_SQUARE => Self::Square,
+            glue::FF_TRIANGLE => Self::Triangle,
+            glue::FF_SINE => Self::Sine,
+            glue::FF_SAW_UP => Self::SawUp,
+            glue::FF_SAW_DOWN => Self::SawDown,
+            glue::FF_GAIN => Self::Gain,
+            glue::FF_AUTOCENTER => Self::Autocenter,
+            _ => return None,
+        };
+
+        Some(ff)
+    }
+
+    fn to_raw(&self) -> Option<Self::Raw> {
+        let code = match self {
+            Self::Rumble => glue::FF_RUMBLE,
+            Self::Periodic => glue::FF_PERIODIC,
+            Self::Constant => glue::FF_CONSTANT,
+            Self::Spring => glue::FF_SPRING,
+            Self::Friction => glue::FF_FRICTION,
+            Self::Damper => glue::FF_DAMPER,
+            Self::Inertia => glue::FF_INERTIA,
+            Self::Ramp => glue::FF_RAMP,
+            Self::Square => glue::FF_SQUARE,
+            Self::Triangle => glue::FF_TRIANGLE,
+            Self::Sine => glue::FF_SINE,
+            Self::SawUp => glue::FF_SAW_UP,
+            Self::SawDown => glue::FF_SAW_DOWN,
+            Self::Gain => glue::FF_GAIN,
+            Self::Autocenter => glue::FF_AUTOCENTER,
+        };
+
+        Some(code as _)
+    }
+}
+
+impl Convert for FfWaveform {
+    type Raw = u16;
+
+    fn from_raw(code: Self::Raw) -> Option<Self> {
+        let waveform = match code as _ {
+            glue::FF_SQUARE => Self::Square,
+            glue::FF_TRIANGLE => Self::Triangle,
+            glue::FF_SINE => Self::Sine,
+            glue::FF_SAW_UP => Self::SawUp,
+            glue::FF_SAW_DOWN => Self::SawDown,
+            _ => return None,
+        };
+
+        Some(waveform)
+    }
+
+    fn to_raw(&self) -> Option<Self::Raw> {
+        let code = match self {
+            Self::Square => glue::FF_SQUARE,
+            Self::Triangle => glue::FF_TRIANGLE,
+            Self::Sine => glue::FF_SINE,
+            Self::SawUp => glue::FF_SAW_UP,
+            Self::SawDown => glue::FF_SAW_DOWN,
+        };
+
+        Some(code as _)
+    }
+}
+
+impl Convert for FfEffect {
+    type Raw = glue::ff_effect;
+
+    fn from_raw(raw: Self::Raw) -> Option<Self> {
+        // The union is only read according to the effect type.
+        let kind = unsafe {
+            match raw.type_ as _ {
+                glue::FF_RUMBLE => FfKind::Rumble {
+                    strong_magnitude: raw.u.rumble.strong_magnitude,
+                    weak_magnitude: raw.u.rumble.weak_magnitude,
+                },
+                glue::FF_PERIODIC => FfKind::Periodic {
+                    waveform: FfWaveform::from_raw(raw.u.periodic.waveform)?,
+                    period: raw.u.periodic.period,
+                    magnitude: raw.u.periodic.magnitude,
+                    offset: raw.u.periodic.offset,
+                    phase: raw.u.periodic.phase,
+                    envelope: envelope_from_raw(raw.u.periodic.envelope),
+                },
+                glue::FF_CONSTANT => FfKind::Constant {
+                    level: raw.u.constant.level,
+                    envelope: envelope_from_raw(raw.u.constant.envelope),
+                },
+                glue::FF_RAMP => FfKind::Ramp {
+                    start_level: raw.u.ramp.start_level,
+                    end_level: raw.u.ramp.end_level,
+                    envelope: envelope_from_raw(raw.u.ramp.envelope),
+                },
+                glue::FF_SPRING => FfKind::Spring(raw.u.condition.map(condition_from_raw)),
+                glue::FF_FRICTION => FfKind::Friction(raw.u.condition.map(condition_from_raw)),
+                glue::FF_DAMPER => FfKind::Damper(raw.u.condition.map(condition_from_raw)),
+                glue::FF_INERTIA => FfKind::Inertia(raw.u.condition.map(condition_from_raw)),
+                _ => return None,
+            }
+        };
+
+        Some(Self {
+            id: raw.id,
+            direction: raw.direction,
+            trigger: FfTrigger {
+                button: raw.trigger.button,
+                interval: raw.trigger.interval,
+            },
+            replay: FfReplay {
+                length: raw.replay.length,
+                delay: raw.replay.delay,
+            },
+            kind,
+        })
+    }
+
+    fn to_raw(&self) -> Option<Self::Raw> {
+        let mut raw: glue::ff_effect = unsafe { mem::zeroed() };
+
+        raw.id = self.id;
+        raw.direction = self.direction;
+        raw.trigger = glue::ff_trigger {
+            button: self.trigger.button,
+            interval: self.trigger.interval,
+        };
+        raw.replay = glue::ff_replay {
+            length: self.replay.length,
+            delay: self.replay.delay,
+        };
+
+        let r#type = match self.kind {
+            FfKind::Rumble {
+                strong_magnitude,
+                weak_magnitude,
+            } => {
+                raw.u.rumble = glue::ff_rumble_effect {
+                    strong_magnitude,
+                    weak_magnitude,
+                };
+
+                glue::FF_RUMBLE
+            }
+            FfKind::Periodic {
+                waveform,
+                period,
+                magnitude,
+                offset,
+                phase,
+                envelope,
+            } => {
+                raw.u.periodic = glue::ff_periodic_effect {
+                    waveform: waveform.to_raw()?,
+                    period,
+                    magnitude,
+                    offset,
+                    phase,
+                    envelope: envelope_to_raw(envelope),
+                    custom_len: 0,
+                    custom_data: std::ptr::null_mut(),
+                };
+
+                glue::FF_PERIODIC
+            }
+            FfKind::Constant { level, envelope } => {
+                raw.u.constant = glue::ff_constant_effect {
+                    level,
+                    envelope: envelope_to_raw(envelope),
+                };
+
+                glue::FF_CONSTANT
+            }
+            FfKind::Ramp {
+                start_level,
+                end_level,
+                envelope,
+            } => {
+                raw.u.ramp = glue::ff_ramp_effect {
+                    start_level,
+                    end_level,
+                    envelope: envelope_to_raw(envelope),
+                };
+
+                glue::FF_RAMP
+            }
+            FfKind::Spring(condition) => {
+                raw.u.condition = condition.map(condition_to_raw);
+                glue::FF_SPRING
+            }
+            FfKind::Friction(condition) => {
+                raw.u.condition = condition.map(condition_to_raw);
+                glue::FF_FRICTION
+            }
+            FfKind::Damper(condition) => {
+                raw.u.condition = condition.map(condition_to_raw);
+                glue::FF_DAMPER
+            }
+            FfKind::Inertia(condition) => {
+                raw.u.condition = condition.map(condition_to_raw);
+                glue::FF_INERTIA
+            }
+        };
+
+        raw.type_ = r#type as _;
+
+        Some(raw)
+    }
+}
+
+fn envelope_from_raw(raw: glue::ff_envelope) -> FfEnvelope {
+    FfEnvelope {
+        attack_length: raw.attack_length,
+        attack_level: raw.attack_level,
+        fade_length: raw.fade_length,
+        fade_level: raw.fade_level,
+    }
+}
+
+fn envelope_to_raw(envelope: FfEnvelope) -> glue::ff_envelope {
+    glue::ff_envelope {
+        attack_length: envelope.attack_length,
+        attack_level: envelope.attack_level,
+        fade_length: envelope.fade_length,
+        fade_level: envelope.fade_level,
+    }
+}
+
+fn condition_from_raw(raw: glue::ff_condition_effect) -> FfCondition {
+    FfCondition {
+        right_saturation: raw.right_saturation,
+        left_saturation: raw.left_saturation,
+        right_coeff: raw.right_coeff,
+        left_coeff: raw.left_coeff,
+        deadband: raw.deadband,
+        center: raw.center,
+    }
+}
+
+fn condition_to_raw(condition: FfCondition) -> glue::ff_condition_effect {
+    glue::ff_condition_effect {
+        right_saturation: condition.right_saturation,
+        left_saturation: condition.left_saturation,
+        right_coeff: condition.right_coeff,
+        left_coeff: condition.left_coeff,
+        deadband: condition.deadband,
+        center: condition.center,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn effects_round_trip() {
+        let envelope = FfEnvelope {
+            attack_length: 100,
+            attack_level: 0x1000,
+            fade_length: 200,
+            fade_level: 0x2000,
+        };
+
+        let condition = |coeff| FfCondition {
+            right_saturation: 0xffff,
+            left_saturation: 0x8000,
+            right_coeff: coeff,
+            left_coeff: -coeff,
+            deadband: 16,
+            center: -32,
+        };
+        let conditions = [condition(0x1234), condition(-0x4321)];
+
+        let kinds = [
+            FfKind::Rumble {
+                strong_magnitude: 0xc000,
+                weak_magnitude: 0x4000,
+            },
+            FfKind::Periodic {
+                waveform: FfWaveform::SawDown,
+                period: 50,
+                magnitude: -0x3000,
+                offset: 0x100,
+                phase: 90,
+                envelope,
+            },
+            FfKind::Constant {
+                level: -0x7000,
+                envelope,
+            },
+            FfKind::Ramp {
+                start_level: -0x1000,
+                end_level: 0x1000,
+                envelope,
+            },
+            FfKind::Spring(conditions),
+            FfKind::Friction(conditions),
+            FfKind::Damper(conditions),
+            FfKind::Inertia(conditions),
+        ];
+
+        for kind in kinds {
+            let effect = FfEffect {
+                id: 3,
+                direction: 0x4000,
+                trigger: FfTrigger {
+                    button: 0x120,
+                    interval: 10,
+                },
+                replay: FfReplay {
+                    length: 1000,
+                    delay: 20,
+                },
+                kind,
+            };
+
+            let raw = effect.to_raw().unwrap();
+            assert_eq!(FfEffect::from_raw(raw), Some(effect));
+        }
+    }
+
+    #[test]
+    fn custom_waveforms_are_rejected() {
+        let mut raw: glue::ff_effect = unsafe { mem::zeroed() };
+        raw.type_ = glue::FF_PERIODIC as _;
+        raw.u.periodic.waveform = glue::FF_CUSTOM as _;
+
+        assert_eq!(FfEffect::from_raw(raw), None);
+    }
+}
@@ -5,14 +5,18 @@ pub use caps::{AbsCaps, KeyCaps, RelCaps};
 use crate::abs::{AbsAxis, AbsInfo, AbsEvent, ToolType};
 use crate::interceptor::{InterceptorPlatform,Repeat};
 use crate::convert::Convert;
-use crate::device::DeviceSpec;
+use crate::device::{Bus, DeviceClass, DeviceInfo, DeviceSpec};
 use crate::linux::evdev::Evdev;
 use crate::event::Event;
+use crate::ff::{Ff, FfEffect};
 use crate::linux::glue;
+use crate::linux::ioctl;
 use crate::key::{Key, KeyEvent};
+use crate::led::{Led, LedEvent};
 use crate::linux::registry::{Entry, Handle, Registry};
 use crate::linux::writer::WriterLinux;
 use crate::writer::WriterPlatform;
+use crate::raw::RawEvent;
 use crate::rel::{RelAxis, RelEvent};
 use crate::sync::SyncEvent;
 use crate::writer::Writer;
@@ -21,12 +25,14 @@ use std::collections::{HashMap, HashSet, VecDeque};
 use std::ffi::CStr;
 use std::fs;
 use std::io::{Error, ErrorKind};
-use std::mem::MaybeUninit;
+use std::mem::{self, MaybeUninit};
+use std::os::fd::AsRawFd;
 use std::path::Path;
 use thiserror::Error;
 
 pub struct InterceptorLinux {
     evdev: Evdev,
+    info: DeviceInfo,
     writer: Writer,
     // The state of `read` is stored here to make it cancel safe.
     events: VecDeque<Event>,
@@ -38,19 +44,35 @@ pub struct InterceptorLinux {
 }
 
 impl InterceptorLinux {
-    #[tracing::instrument(skip(registry, device_allowlist))]
-    pub(crate) async fn open(path: &Path, registry: &Registry, device_allowlist: &[DeviceSpec]) -> Result<Self, OpenError> {
+    #[tracing::instrument(skip(registry, device_allowlist, device_denylist))]
+    pub(crate) async fn open(
+        path: &Path,
+        registry: &Registry,
+        device_allowlist: &[DeviceSpec],
+        device_denylist: &[DeviceSpec],
+        switches: bool,
+    ) -> Result<Self, OpenError> {
         let evdev = Evdev::open(path).await?;
 
-		if !device_allowlist.is_empty() {
-			let name = evdev.name();
-			let vendor_id = evdev.vendor();
-			let product_id = evdev.product();
+        // This has to happen before the device is modified below, the bus type is overwritten for one.
+        let info = DeviceInfo {
+            name: evdev.name().to_owned(),
+            vendor: evdev.vendor(),
+            product: evdev.product(),
+            bus: Bus::from_raw(evdev.bustype()),
+            phys: evdev.phys().map(CStr::to_owned),
+            uniq: evdev.uniq().map(CStr::to_owned),
+            classes: DeviceClass::classify(
+                &RelCaps::new(&evdev).collect(),
+                &AbsCaps::new(&evdev).map(|(axis, _)| axis).collect(),
+                &KeyCaps::new(&evdev).collect(),
+            ),
+        };
+
+        if !info.allowed(device_allowlist, device_denylist) {
+            return Err(OpenError::NotAllowed);
+        }
 
-			if !device_allowlist.iter().any(|check| check.matches(&name, &vendor_id, &product_id)) {
-				return Err(OpenError::NotMatchingAllowlist);
-			}
-		}
         let metadata = evdev.file().unwrap().get_ref().metadata()?;
 
         let reader_handle = registry
@@ -63,7 +85,18 @@ impl InterceptorLinux {
         // We have no way of knowing that.
         let sw = unsafe { glue::libevdev_has_event_type(evdev.as_ptr(), glue::EV_SW) };
         if sw == 1 {
-            return Err(OpenError::NotAppliable);
+            if !switches {
+                return Err(OpenError::NotAppliable);
+            }
+
+            // Forward the rest of the device. Disabled events are discarded by libevdev
+            // and the writer is created without the switches too.
+            tracing::info!("Dropping switch events of {:?}", path);
+
+            let ret = unsafe { glue::libevdev_disable_event_type(evdev.as_ptr(), glue::EV_SW) };
+            if ret < 0 {
+                return Err(Error::from_raw_os_error(-ret).into());
+            }
         }
 
         // Some buggy kernels can report nonsense abs info, so check for it and disable the axes.
@@ -121,15 +154,16 @@ impl InterceptorLinux {
         let writer = WriterLinux::from_evdev(&evdev).await?;
         let path = writer
             .path()
-            .ok_or_else(|| Error::new(ErrorKind::Other, "No syspath for writer"))?;
+            .ok_or_else(|| Error::other("No syspath for writer"))?;
 
         let metadata = fs::metadata(path)?;
         let writer_handle = registry
             .register(Entry::from_metadata(&metadata))
-            .ok_or_else(|| Error::new(ErrorKind::Other, "Writer already registered"))?;
+            .ok_or_else(|| Error::other("Writer already registered"))?;
 
         Ok(Self {
             evdev,
+            info,
             writer,
             events: VecDeque::new(),
             dropped: false,
@@ -178,6 +212,24 @@ impl InterceptorLinux {
             }
         }
     }
+
+    // Force feedback events go to the device itself rather than to our writer.
+    fn write_ff_raw(&self, code: u16, value: i32) -> Result<(), Error> {
+        let mut event: glue::input_event = unsafe { mem::zeroed() };
+        event.type_ = glue::EV_FF as _;
+        event.code = code;
+        event.value = value;
+
+        let fd = self.evdev.file().unwrap().as_raw_fd();
+        let size = mem::size_of::<glue::input_event>();
+
+        let ret = unsafe { libc::write(fd, &event as *const _ as *const _, size) };
+        if ret < 0 {
+            return Err(Error::last_os_error());
+        }
+
+        Ok(())
+    }
 }
 
 impl InterceptorPlatform for InterceptorLinux {
@@ -193,23 +245,6 @@ impl InterceptorPlatform for InterceptorLinux {
         while !matches!(self.events.back(), Some(Event::Sync(SyncEvent::All))) {
             let (r#type, code, value) = self.read_raw().await?;
             let event = match r#type as _ {
-                glue::EV_REL if !self.dropped => {
-                    RelAxis::from_raw(code).map(|axis| Event::Rel(RelEvent { axis, value }))
-                }
-                glue::EV_ABS if !self.dropped => match code as _ {
-                    glue::ABS_MT_TOOL_TYPE => {
-                        ToolType::from_raw(value).map(|value| AbsEvent::MtToolType { value })
-                    }
-                    _ => AbsAxis::from_raw(code).map(|axis| AbsEvent::Axis { axis, value }),
-                }
-                .map(Event::Abs),
-                glue::EV_KEY if !self.dropped && (value == 0 || value == 1) => Key::from_raw(code)
-                    .map(|key| {
-                        Event::Key(KeyEvent {
-                            key,
-                            down: value == 1,
-                        })
-                    }),
                 glue::EV_SYN => match code as _ {
                     glue::SYN_REPORT => {
                         if self.dropped {
@@ -233,7 +268,8 @@ impl InterceptorPlatform for InterceptorLinux {
                     glue::SYN_MT_REPORT if !self.dropped => Some(Event::Sync(SyncEvent::Mt)),
                     _ => continue,
                 },
-                _ => None,
+                _ if self.dropped => None,
+                _ => convert(r#type, code, value),
             };
 
             if let Some(event) = event {
@@ -241,6 +277,10 @@ impl InterceptorPlatform for InterceptorLinux {
                 continue;
             }
 
+            if !written_back(r#type) {
+                continue;
+            }
+
             self.writing = Some((r#type, code, value));
             self.writer.write_raw(r#type, code, value).await?;
             self.writing = None;
@@ -269,16 +309,20 @@ impl InterceptorPlatform for InterceptorLinux {
 		self.evdev.version()
     }
 
+    fn info(&self) -> &DeviceInfo {
+        &self.info
+    }
+
     fn rel(&self) -> HashSet<RelAxis> {
-        RelCaps::new(self).collect::<HashSet<_>>()
+        RelCaps::new(&self.evdev).collect::<HashSet<_>>()
     }
 
     fn abs(&self) -> HashMap<AbsAxis, AbsInfo> {
-        AbsCaps::new(self).collect::<HashMap<_,_>>()
+        AbsCaps::new(&self.evdev).collect::<HashMap<_, _>>()
     }
 
     fn key(&self) -> HashSet<Key> {
-        KeyCaps::new(self).collect::<HashSet<_>>()
+        KeyCaps::new(&self.evdev).collect::<HashSet<_>>()
     }
 
     fn repeat(&self) -> Repeat {
@@ -321,6 +365,155 @@ impl InterceptorPlatform for InterceptorLinux {
 
         Repeat { delay, period }
     }
+
+    fn raw(&self) -> HashSet<(u16, u16)> {
+        let types = [
+            (glue::EV_REL, glue::REL_CNT),
+            (glue::EV_MSC, glue::MSC_CNT),
+            (glue::EV_SND, glue::SND_CNT),
+        ];
+
+        types
+            .into_iter()
+            .flat_map(|(r#type, count)| (0..count).map(move |code| (r#type, code)))
+            .filter(|(r#type, code)| unsafe {
+                glue::libevdev_has_event_code(self.evdev.as_ptr(), *r#type, *code) == 1
+            })
+            .map(|(r#type, code)| (r#type as u16, code as u16))
+            .filter(|(r#type, code)| forwarded_raw(*r#type, *code))
+            .collect()
+    }
+
+    fn leds(&self) -> HashMap<Led, bool> {
+        (0..glue::LED_CNT)
+            .filter(|code| unsafe {
+                glue::libevdev_has_event_code(self.evdev.as_ptr(), glue::EV_LED, *code) == 1
+            })
+            .filter_map(|code| {
+                let led = Led::from_raw(code as _)?;
+                let on = unsafe {
+                    glue::libevdev_get_event_value(self.evdev.as_ptr(), glue::EV_LED, code) != 0
+                };
+
+                Some((led, on))
+            })
+            .collect()
+    }
+
+    fn set_led(&mut self, event: LedEvent) -> Result<(), Error> {
+        let code = match event.led.to_raw() {
+            Some(code) => code,
+            None => return Ok(()),
+        };
+
+        let value = if event.on {
+            glue::libevdev_led_value_LIBEVDEV_LED_ON
+        } else {
+            glue::libevdev_led_value_LIBEVDEV_LED_OFF
+        };
+
+        let ret =
+            unsafe { glue::libevdev_kernel_set_led_value(self.evdev.as_ptr(), code as _, value) };
+
+        if ret < 0 {
+            return Err(Error::from_raw_os_error(-ret));
+        }
+
+        Ok(())
+    }
+
+    fn ff(&self) -> HashSet<Ff> {
+        (0..glue::FF_CNT)
+            .filter(|code| unsafe {
+                glue::libevdev_has_event_code(self.evdev.as_ptr(), glue::EV_FF, *code) == 1
+            })
+            .filter_map(|code| Ff::from_raw(code as _))
+            .collect()
+    }
+
+    fn upload_ff(&mut self, effect: FfEffect) -> Result<i16, Error> {
+        let mut effect = effect
+            .to_raw()
+            .ok_or_else(|| Error::new(ErrorKind::InvalidInput, "Unsupported effect"))?;
+
+        let fd = self.evdev.file().unwrap().as_raw_fd();
+
+        // The kernel writes the ID of a new effect back.
+        let ret = unsafe { libc::ioctl(fd, ioctl::EVIOCSFF, &mut effect) };
+        if ret < 0 {
+            return Err(Error::last_os_error());
+        }
+
+        Ok(effect.id)
+    }
+
+    fn erase_ff(&mut self, id: i16) -> Result<(), Error> {
+        let fd = self.evdev.file().unwrap().as_raw_fd();
+
+        let ret = unsafe { libc::ioctl(fd, ioctl::EVIOCRMFF, id as libc::c_int) };
+        if ret < 0 {
+            return Err(Error::last_os_error());
+        }
+
+        Ok(())
+    }
+
+    fn play_ff(&mut self, id: i16, count: i32) -> Result<(), Error> {
+        self.write_ff_raw(id as _, count)
+    }
+
+    fn set_ff_gain(&mut self, gain: u16) -> Result<(), Error> {
+        self.write_ff_raw(glue::FF_GAIN as _, gain as _)
+    }
+
+    fn set_ff_autocenter(&mut self, autocenter: u16) -> Result<(), Error> {
+        self.write_ff_raw(glue::FF_AUTOCENTER as _, autocenter as _)
+    }
+}
+
+// Converts an event read from the device, events that aren't forwarded are None and written back locally.
+fn convert(r#type: u16, code: u16, value: i32) -> Option<Event> {
+    match r#type as _ {
+        glue::EV_REL => RelAxis::from_raw(code).map(|axis| Event::Rel(RelEvent { axis, value })),
+        glue::EV_ABS => match code as _ {
+            glue::ABS_MT_TOOL_TYPE => {
+                ToolType::from_raw(value).map(|value| AbsEvent::MtToolType { value })
+            }
+            _ => AbsAxis::from_raw(code).map(|axis| AbsEvent::Axis { axis, value }),
+        }
+        .map(Event::Abs),
+        glue::EV_KEY if value == 0 || value == 1 => Key::from_code(code).map(|key| {
+            Event::Key(KeyEvent {
+                key,
+                down: value == 1,
+            })
+        }),
+        glue::EV_KEY if value == 2 => Key::from_code(code).map(Event::Repeat),
+        _ => None,
+    }
+    .or_else(|| {
+        forwarded_raw(r#type, code).then_some(Event::Raw(RawEvent {
+            r#type,
+            code,
+            value,
+        }))
+    })
+}
+
+// Whether events rkvm doesn't model are forwarded as raw events, other types are either modelled
+// or state of the device the server keeps to itself.
+fn forwarded_raw(r#type: u16, code: u16) -> bool {
+    match r#type as _ {
+        glue::EV_REL => RelAxis::from_raw(code).is_none(),
+        glue::EV_MSC | glue::EV_SND => true,
+        _ => false,
+    }
+}
+
+// Whether events that aren't forwarded are written to the local writer. LEDs of grabbed devices are only
+// set through `set_led` and the kernel reports them back, writing those would feed client state to the system.
+fn written_back(r#type: u16) -> bool {
+    r#type as u32 != glue::EV_LED
 }
 
 unsafe impl Send for InterceptorLinux {}
@@ -329,8 +522,75 @@ unsafe impl Send for InterceptorLinux {}
 pub(crate) enum OpenError {
     #[error("Not appliable")]
     NotAppliable,
-	#[error("Device doesn't match allowlist")]
-	NotMatchingAllowlist,
+    #[error("Device doesn't match the allowlist or matches the denylist")]
+    NotAllowed,
     #[error(transparent)]
     Io(#[from] Error),
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::key::Keyboard;
+
+    #[test]
+    fn unmodelled_events_are_raw() {
+        let raw = |r#type: u32, code: u32, value| {
+            Some(Event::Raw(RawEvent {
+                r#type: r#type as _,
+                code: code as _,
+                value,
+            }))
+        };
+
+        assert_eq!(
+            convert(glue::EV_REL as _, glue::REL_WHEEL as _, -1),
+            Some(Event::Rel(RelEvent {
+                axis: RelAxis::Wheel,
+                value: -1
+            }))
+        );
+        assert_eq!(
+            convert(glue::EV_REL as _, glue::REL_RESERVED as _, 1),
+            raw(glue::EV_REL, glue::REL_RESERVED, 1)
+        );
+        assert_eq!(
+            convert(glue::EV_MSC as _, glue::MSC_SCAN as _, 0x70004),
+            raw(glue::EV_MSC, glue::MSC_SCAN, 0x70004)
+        );
+        assert_eq!(
+            convert(glue::EV_SND as _, glue::SND_BELL as _, 1),
+            raw(glue::EV_SND, glue::SND_BELL, 1)
+        );
+
+        assert_eq!(
+            convert(glue::EV_KEY as _, glue::KEY_A as _, 2),
+            Some(Event::Repeat(Key::Key(Keyboard::A)))
+        );
+
+        // Device state isn't forwarded.
+        assert_eq!(convert(glue::EV_LED as _, glue::LED_CAPSL as _, 1), None);
+        assert_eq!(convert(glue::EV_SW as _, glue::SW_LID as _, 1), None);
+    }
+
+    #[test]
+    fn led_echoes_are_not_written_back() {
+        assert!(!written_back(glue::EV_LED as _));
+        assert!(written_back(glue::EV_SW as _));
+        assert!(written_back(glue::EV_KEY as _));
+    }
+
+    #[test]
+    fn modelled_codes_are_not_raw() {
+        assert!(!forwarded_raw(glue::EV_REL as _, glue::REL_X as _));
+        assert!(!forwarded_raw(
+            glue::EV_REL as _,
+            glue::REL_HWHEEL_HI_RES as _
+        ));
+        assert!(forwarded_raw(glue::EV_REL as _, glue::REL_RESERVED as _));
+        assert!(forwarded_raw(glue::EV_MSC as _, glue::MSC_SCAN as _));
+        assert!(forwarded_raw(glue::EV_SND as _, glue::SND_BELL as _));
+        assert!(!forwarded_raw(glue::EV_KEY as _, glue::KEY_A as _));
+        assert!(!forwarded_raw(glue::EV_LED as _, glue::LED_CAPSL as _));
+    }
+}
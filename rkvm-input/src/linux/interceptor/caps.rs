@@ -1,23 +1,23 @@
 use crate::abs::{AbsAxis, AbsInfo};
 use crate::convert::Convert;
 use crate::linux::glue;
-use crate::linux::interceptor::InterceptorLinux;
+use crate::linux::evdev::Evdev;
 use crate::key::Key;
 use crate::rel::RelAxis;
 
 pub struct RelCaps<'a> {
     current: u16,
-    interceptor: &'a InterceptorLinux,
+    evdev: &'a Evdev,
 }
 
 impl<'a> RelCaps<'a> {
-    pub(super) fn new(interceptor: &'a InterceptorLinux) -> Self {
+    pub(super) fn new(evdev: &'a Evdev) -> Self {
         let has =
-            unsafe { glue::libevdev_has_event_type(interceptor.evdev.as_ptr(), glue::EV_REL) == 1 };
+            unsafe { glue::libevdev_has_event_type(evdev.as_ptr(), glue::EV_REL) == 1 };
 
         Self {
             current: if has { 0 } else { glue::REL_MAX as _ },
-            interceptor,
+            evdev,
         }
     }
 }
@@ -29,7 +29,7 @@ impl Iterator for RelCaps<'_> {
         while self.current < glue::REL_MAX as _ {
             let has = unsafe {
                 glue::libevdev_has_event_code(
-                    self.interceptor.evdev.as_ptr(),
+                    self.evdev.as_ptr(),
                     glue::EV_REL,
                     self.current as _,
                 ) == 1
@@ -52,17 +52,17 @@ impl Iterator for RelCaps<'_> {
 
 pub struct AbsCaps<'a> {
     current: u16,
-    interceptor: &'a InterceptorLinux,
+    evdev: &'a Evdev,
 }
 
 impl<'a> AbsCaps<'a> {
-    pub(super) fn new(interceptor: &'a InterceptorLinux) -> Self {
+    pub(super) fn new(evdev: &'a Evdev) -> Self {
         let has =
-            unsafe { glue::libevdev_has_event_type(interceptor.evdev.as_ptr(), glue::EV_ABS) == 1 };
+            unsafe { glue::libevdev_has_event_type(evdev.as_ptr(), glue::EV_ABS) == 1 };
 
         Self {
             current: if has { 0 } else { glue::ABS_MAX as _ },
-            interceptor,
+            evdev,
         }
     }
 }
@@ -74,7 +74,7 @@ impl Iterator for AbsCaps<'_> {
         while self.current < glue::ABS_MAX as _ {
             let has = unsafe {
                 glue::libevdev_has_event_code(
-                    self.interceptor.evdev.as_ptr(),
+                    self.evdev.as_ptr(),
                     glue::EV_ABS,
                     self.current as _,
                 ) == 1
@@ -89,7 +89,7 @@ impl Iterator for AbsCaps<'_> {
             if let Some(axis) = AbsAxis::from_raw(self.current - 1) {
                 let info = unsafe {
                     glue::libevdev_get_abs_info(
-                        self.interceptor.evdev.as_ptr(),
+                        self.evdev.as_ptr(),
                         (self.current - 1) as _,
                     )
                 };
@@ -113,17 +113,17 @@ impl Iterator for AbsCaps<'_> {
 
 pub struct KeyCaps<'a> {
     current: u16,
-    interceptor: &'a InterceptorLinux,
+    evdev: &'a Evdev,
 }
 
 impl<'a> KeyCaps<'a> {
-    pub(super) fn new(interceptor: &'a InterceptorLinux) -> Self {
+    pub(super) fn new(evdev: &'a Evdev) -> Self {
         let has =
-            unsafe { glue::libevdev_has_event_type(interceptor.evdev.as_ptr(), glue::EV_KEY) == 1 };
+            unsafe { glue::libevdev_has_event_type(evdev.as_ptr(), glue::EV_KEY) == 1 };
 
         Self {
             current: if has { 0 } else { glue::KEY_MAX as _ },
-            interceptor,
+            evdev,
         }
     }
 }
@@ -135,7 +135,7 @@ impl Iterator for KeyCaps<'_> {
         while self.current < glue::KEY_MAX as _ {
             let has = unsafe {
                 glue::libevdev_has_event_code(
-                    self.interceptor.evdev.as_ptr(),
+                    self.evdev.as_ptr(),
                     glue::EV_KEY,
                     self.current as _,
                 ) == 1
@@ -147,7 +147,7 @@ impl Iterator for KeyCaps<'_> {
                 continue;
             }
 
-            if let Some(stroke) = Key::from_raw(self.current - 1) {
+            if let Some(stroke) = Key::from_code(self.current - 1) {
                 return Some(stroke);
             }
         }
@@ -0,0 +1,33 @@
+use crate::linux::glue;
+
+use libc::{c_int, Ioctl, _IOW, _IOWR};
+
+// Request numbers of ioctls bindgen can't translate, since they're defined using function-like macros.
+// The libc functions follow the encoding of the target architecture.
+
+pub const UI_BEGIN_FF_UPLOAD: Ioctl = _IOWR::<glue::uinput_ff_upload>(b'U' as _, 200);
+
+pub const UI_END_FF_UPLOAD: Ioctl = _IOW::<glue::uinput_ff_upload>(b'U' as _, 201);
+
+pub const UI_BEGIN_FF_ERASE: Ioctl = _IOWR::<glue::uinput_ff_erase>(b'U' as _, 202);
+
+pub const UI_END_FF_ERASE: Ioctl = _IOW::<glue::uinput_ff_erase>(b'U' as _, 203);
+
+pub const EVIOCSFF: Ioctl = _IOW::<glue::ff_effect>(b'E' as _, 0x80);
+
+pub const EVIOCRMFF: Ioctl = _IOW::<c_int>(b'E' as _, 0x81);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    #[cfg(target_arch = "x86_64")]
+    fn request_numbers() {
+        // As found with the C macros.
+        assert_eq!(EVIOCSFF, 0x40304580);
+        assert_eq!(EVIOCRMFF, 0x40044581);
+        assert_eq!(UI_BEGIN_FF_UPLOAD, 0xc06855c8);
+        assert_eq!(UI_END_FF_ERASE, 0x400c55cb);
+    }
+}
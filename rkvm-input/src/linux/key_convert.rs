@@ -1,11 +1,13 @@
 use crate::convert::Convert;
+use crate::linux::glue;
 
 use crate::key::{Key,Keyboard,Button};
 
 impl Convert for Key {
     type Raw = u16;
 
-    fn from_raw(code: Self::Raw) -> Option<Self> {
+    // `Self::Raw` would be ambiguous with `Key::Raw`.
+    fn from_raw(code: <Self as Convert>::Raw) -> Option<Self> {
         if let Some(key) = Keyboard::from_raw(code) {
             return Some(Self::Key(key));
         }
@@ -21,6 +23,35 @@ impl Convert for Key {
         match self {
             Self::Key(key) => key.to_raw(),
             Self::Button(button) => button.to_raw(),
+            Key::Raw(code) => (u32::from(*code) < glue::KEY_CNT).then_some(*code),
         }
     }
 }
+
+impl Key {
+    /// Returns the key with the given Linux key code, named if possible, None if it's not a key code.
+    pub fn from_code(code: u16) -> Option<Self> {
+        Self::from_raw(code).or_else(|| (u32::from(code) < glue::KEY_CNT).then_some(Key::Raw(code)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn unnamed_codes_are_raw() {
+        assert_eq!(Key::from_raw(glue::KEY_A as _), Some(Key::Key(Keyboard::A)));
+        assert_eq!(Key::from_raw(249), None);
+
+        assert_eq!(
+            Key::from_code(glue::KEY_A as _),
+            Some(Key::Key(Keyboard::A))
+        );
+        assert_eq!(Key::from_code(249), Some(Key::Raw(249)));
+        assert_eq!(Key::from_code(glue::KEY_CNT as _), None);
+
+        assert_eq!(Key::Raw(249).to_raw(), Some(249));
+        assert_eq!(Key::Raw(glue::KEY_CNT as _).to_raw(), None);
+    }
+}
@@ -0,0 +1,75 @@
+use crate::convert::Convert;
+use crate::linux::glue;
+
+use crate::led::Led;
+
+impl Convert for Led {
+    type Raw = u16;
+
+    fn from_raw(code: Self::Raw) -> Option<Self> {
+        let led = match code as _ {
+            glue::LED_NUML => Self::NumLock,
+            glue::LED_CAPSL => Self::CapsLock,
+            glue::LED_SCROLLL => Self::ScrollLock,
+            glue::LED_COMPOSE => Self::Compose,
+            glue::LED_KANA => Self::Kana,
+            glue::LED_SLEEP => Self::Sleep,
+            glue::LED_SUSPEND => Self::Suspend,
+            glue::LED_MUTE => Self::Mute,
+            glue::LED_MISC => Self::Misc,
+            glue::LED_MAIL => Self::Mail,
+            glue::LED_CHARGING => Self::Charging,
+            _ => return None,
+        };
+
+        Some(led)
+    }
+
+    fn to_raw(&self) -> Option<Self::Raw> {
+        let code = match self {
+            Self::NumLock => glue::LED_NUML,
+            Self::CapsLock => glue::LED_CAPSL,
+            Self::ScrollLock => glue::LED_SCROLLL,
+            Self::Compose => glue::LED_COMPOSE,
+            Self::Kana => glue::LED_KANA,
+            Self::Sleep => glue::LED_SLEEP,
+            Self::Suspend => glue::LED_SUSPEND,
+            Self::Mute => glue::LED_MUTE,
+            Self::Misc => glue::LED_MISC,
+            Self::Mail => glue::LED_MAIL,
+            Self::Charging => glue::LED_CHARGING,
+        };
+
+        Some(code as _)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn leds_round_trip() {
+        let leds = [
+            Led::NumLock,
+            Led::CapsLock,
+            Led::ScrollLock,
+            Led::Compose,
+            Led::Kana,
+            Led::Sleep,
+            Led::Suspend,
+            Led::Mute,
+            Led::Misc,
+            Led::Mail,
+            Led::Charging,
+        ];
+
+        for led in leds {
+            let raw = led.to_raw().unwrap();
+            assert_eq!(Led::from_raw(raw), Some(led));
+        }
+
+        assert_eq!(Led::CapsLock.to_raw(), Some(glue::LED_CAPSL as _));
+        assert_eq!(Led::from_raw(glue::LED_MAX as _), None);
+    }
+}
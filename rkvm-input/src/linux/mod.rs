@@ -3,13 +3,17 @@ pub mod monitor;
 
 mod evdev;
 mod glue;
+mod ioctl;
 mod uinput;
 mod registry;
 mod interceptor;
 
 mod abs_convert;
+mod bus_convert;
 mod button_convert;
+mod ff_convert;
 mod keyboard_convert;
 mod key_convert;
+mod led_convert;
 mod rel_convert;
 mod sync_convert;
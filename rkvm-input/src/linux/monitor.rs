@@ -19,9 +19,13 @@ pub struct MonitorLinux {
 
 impl MonitorPlatform for MonitorLinux {
     type Interceptor = InterceptorLinux;
-    fn new(device_allowlist: Vec<DeviceSpec>) -> Self {
+    fn new(
+        device_allowlist: Vec<DeviceSpec>,
+        device_denylist: Vec<DeviceSpec>,
+        switches: bool,
+    ) -> Self {
         let (sender, receiver) = mpsc::channel(1);
-        tokio::spawn(monitor(sender, device_allowlist));
+        tokio::spawn(monitor(sender, device_allowlist, device_denylist, switches));
 
         Self { receiver }
     }
@@ -34,7 +38,12 @@ impl MonitorPlatform for MonitorLinux {
     }
 }
 
-async fn monitor(sender: Sender<Result<InterceptorLinux, Error>>, device_allowlist: Vec<DeviceSpec>) {
+async fn monitor(
+    sender: Sender<Result<InterceptorLinux, Error>>,
+    device_allowlist: Vec<DeviceSpec>,
+    device_denylist: Vec<DeviceSpec>,
+    switches: bool,
+) {
     let run = async {
         let registry = Registry::new();
 
@@ -67,17 +76,31 @@ async fn monitor(sender: Sender<Result<InterceptorLinux, Error>>, device_allowli
             if !path
                 .file_name()
                 .and_then(OsStr::to_str)
-                .map_or(false, |name| name.starts_with("event"))
+                .is_some_and(|name| name.starts_with("event"))
             {
                 tracing::debug!("Skipping non event file {:?}", path);
                 continue;
             }
 
-            let interceptor = match InterceptorLinux::open(&path, &registry, &device_allowlist).await {
+            let interceptor = match InterceptorLinux::open(
+                &path,
+                &registry,
+                &device_allowlist,
+                &device_denylist,
+                switches,
+            )
+            .await
+            {
                 Ok(interceptor) => interceptor,
                 Err(OpenError::Io(err)) => return Err(err),
                 Err(OpenError::NotAppliable) => continue,
-				Err(OpenError::NotMatchingAllowlist) => continue,
+                Err(OpenError::NotAllowed) => {
+                    tracing::debug!(
+                        "Skipping {:?} as it's not allowed by the device lists",
+                        path
+                    );
+                    continue;
+                }
             };
 
             if sender.send(Ok(interceptor)).await.is_err() {
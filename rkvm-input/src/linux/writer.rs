@@ -1,17 +1,23 @@
 use libc::c_int;
 
-use crate::writer::{WriterPlatform,WriterBuilderPlatform};
+use crate::writer::{Output, WriterBuilderPlatform, WriterPlatform};
 use crate::abs::{AbsAxis, AbsEvent, AbsInfo};
 use crate::convert::Convert;
 use crate::event::Event;
+use crate::ff::{Ff, FfEffect, FfEvent};
 use crate::linux::glue::{self, input_absinfo};
+use crate::linux::ioctl;
 use crate::key::{Key, KeyEvent};
+use crate::led::{Led, LedEvent};
+use crate::raw::RawEvent;
 use crate::rel::{RelAxis, RelEvent};
 use crate::linux::uinput::Uinput;
 use crate::linux::evdev::Evdev;
 
 use std::ffi::{CString, CStr, OsStr};
-use std::io::Error;
+use std::io::{Error, ErrorKind};
+use std::mem::{self, MaybeUninit};
+use std::os::fd::AsRawFd;
 use std::os::unix::ffi::OsStrExt;
 use std::path::Path;
 use std::ptr;
@@ -58,7 +64,7 @@ impl WriterLinux {
                 };
 
                 if ret < 0 {
-                    return Err(Error::from_raw_os_error(-ret).into());
+                    return Err(Error::from_raw_os_error(-ret));
                 }
 
                 Ok(())
@@ -70,6 +76,81 @@ impl WriterLinux {
             }
         }
     }
+
+    // Reads an event written to the device by the system, such as an LED change or a force feedback request.
+    async fn read_raw(&mut self) -> Result<(u16, u16, i32), Error> {
+        loop {
+            let result = self.uinput.file().readable().await?.try_io(|file| {
+                let mut event = MaybeUninit::<glue::input_event>::uninit();
+                let size = mem::size_of::<glue::input_event>();
+
+                let ret =
+                    unsafe { libc::read(file.as_raw_fd(), event.as_mut_ptr() as *mut _, size) };
+                if ret < 0 {
+                    return Err(Error::last_os_error());
+                }
+
+                if ret as usize != size {
+                    return Err(Error::new(
+                        ErrorKind::UnexpectedEof,
+                        "Short read from uinput",
+                    ));
+                }
+
+                let event = unsafe { event.assume_init() };
+                Ok((event.type_, event.code, event.value))
+            });
+
+            match result {
+                Ok(result) => return result,
+                Err(_) => continue, // This means it would block.
+            }
+        }
+    }
+
+    // Takes an effect from the system. Effects we can't represent are refused right away.
+    fn upload(&self, request_id: u32) -> Result<Option<FfEffect>, Error> {
+        let fd = self.uinput.file().as_raw_fd();
+
+        let mut upload: glue::uinput_ff_upload = unsafe { mem::zeroed() };
+        upload.request_id = request_id;
+
+        let ret = unsafe { libc::ioctl(fd, ioctl::UI_BEGIN_FF_UPLOAD, &mut upload) };
+        if ret < 0 {
+            return Err(Error::last_os_error());
+        }
+
+        let effect = FfEffect::from_raw(upload.effect);
+        upload.retval = if effect.is_some() { 0 } else { -libc::EINVAL };
+
+        let ret = unsafe { libc::ioctl(fd, ioctl::UI_END_FF_UPLOAD, &upload) };
+        if ret < 0 {
+            return Err(Error::last_os_error());
+        }
+
+        Ok(effect)
+    }
+
+    fn erase(&self, request_id: u32) -> Result<i16, Error> {
+        let fd = self.uinput.file().as_raw_fd();
+
+        let mut erase: glue::uinput_ff_erase = unsafe { mem::zeroed() };
+        erase.request_id = request_id;
+
+        let ret = unsafe { libc::ioctl(fd, ioctl::UI_BEGIN_FF_ERASE, &mut erase) };
+        if ret < 0 {
+            return Err(Error::last_os_error());
+        }
+
+        erase.retval = 0;
+
+        let ret = unsafe { libc::ioctl(fd, ioctl::UI_END_FF_ERASE, &erase) };
+        if ret < 0 {
+            return Err(Error::last_os_error());
+        }
+
+        Ok(erase.effect_id as _)
+    }
 }
 
 impl WriterPlatform for WriterLinux {
@@ -91,7 +172,13 @@ impl WriterPlatform for WriterLinux {
                 ),
             },
             Event::Key(KeyEvent { down, key }) => (glue::EV_KEY, key.to_raw(), Some(*down as _)),
+            Event::Repeat(key) => (glue::EV_KEY, key.to_raw(), Some(2)),
             Event::Sync(event) => (glue::EV_SYN, event.to_raw(), Some(0)),
+            Event::Raw(RawEvent {
+                r#type,
+                code,
+                value,
+            }) => (*r#type as _, Some(*code), Some(*value)),
         };
 
         if let (Some(code), Some(value)) = (code, value) {
@@ -100,6 +187,40 @@ impl WriterPlatform for WriterLinux {
 
         Ok(())
     }
+
+    async fn read(&mut self) -> Result<Output, Error> {
+        loop {
+            let (r#type, code, value) = self.read_raw().await?;
+            let output = match (r#type as _, code as _) {
+                (glue::EV_LED, _) => Led::from_raw(code).map(|led| {
+                    Output::Led(LedEvent {
+                        led,
+                        on: value != 0,
+                    })
+                }),
+                (glue::EV_FF, glue::FF_GAIN) => Some(Output::Ff(FfEvent::Gain(value as _))),
+                (glue::EV_FF, glue::FF_AUTOCENTER) => {
+                    Some(Output::Ff(FfEvent::Autocenter(value as _)))
+                }
+                (glue::EV_FF, _) => Some(Output::Ff(FfEvent::Play {
+                    id: code as _,
+                    count: value,
+                })),
+                // The system waits for these to be handled, so do it right here.
+                (glue::EV_UINPUT, glue::UI_FF_UPLOAD) => self
+                    .upload(value as _)?
+                    .map(|effect| Output::Ff(FfEvent::Upload(effect))),
+                (glue::EV_UINPUT, glue::UI_FF_ERASE) => Some(Output::Ff(FfEvent::Erase {
+                    id: self.erase(value as _)?,
+                })),
+                _ => None,
+            };
+
+            if let Some(output) = output {
+                return Ok(output);
+            }
+        }
+    }
 }
 
 pub struct WriterLinuxBuilder {
@@ -248,6 +369,73 @@ impl WriterBuilderPlatform for WriterLinuxBuilder {
         Ok(self)
     }
 
+    fn led<T: IntoIterator<Item = Led>>(self, items: T) -> Result<Self, Error> {
+        for led in items {
+            let led = match led.to_raw() {
+                Some(led) => led,
+                None => continue,
+            };
+
+            let ret = unsafe {
+                glue::libevdev_enable_event_code(
+                    self.evdev.as_ptr(),
+                    glue::EV_LED,
+                    led as _,
+                    ptr::null(),
+                )
+            };
+
+            if ret < 0 {
+                return Err(Error::from_raw_os_error(-ret));
+            }
+        }
+
+        Ok(self)
+    }
+
+    fn ff<T: IntoIterator<Item = Ff>>(self, items: T) -> Result<Self, Error> {
+        for ff in items {
+            let ff = match ff.to_raw() {
+                Some(ff) => ff,
+                None => continue,
+            };
+
+            let ret = unsafe {
+                glue::libevdev_enable_event_code(
+                    self.evdev.as_ptr(),
+                    glue::EV_FF,
+                    ff as _,
+                    ptr::null(),
+                )
+            };
+
+            if ret < 0 {
+                return Err(Error::from_raw_os_error(-ret));
+            }
+        }
+
+        Ok(self)
+    }
+
+    fn raw<T: IntoIterator<Item = (u16, u16)>>(self, items: T) -> Result<Self, Error> {
+        for (r#type, code) in items {
+            let ret = unsafe {
+                glue::libevdev_enable_event_code(
+                    self.evdev.as_ptr(),
+                    r#type as _,
+                    code as _,
+                    ptr::null(),
+                )
+            };
+
+            if ret < 0 {
+                return Err(Error::from_raw_os_error(-ret));
+            }
+        }
+
+        Ok(self)
+    }
+
     fn delay(self, value: Option<i32>) -> Result<Self, Error> {
         let value: c_int = match value {
             Some(value) => value,
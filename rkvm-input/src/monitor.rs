@@ -10,7 +10,14 @@ pub use {crate::linux::monitor::MonitorLinux as Monitor};
 pub trait MonitorPlatform: Sized {
     type Interceptor: InterceptorPlatform;
 
-    fn new(device_allowlist: Vec<DeviceSpec>) -> Self;
+    /// Only devices matching an entry of the allowlist (if it isn't empty) and none of the denylist are read.
+    ///
+    /// Devices with switches are skipped unless `switches` is set, their switch events are dropped then.
+    fn new(
+        device_allowlist: Vec<DeviceSpec>,
+        device_denylist: Vec<DeviceSpec>,
+        switches: bool,
+    ) -> Self;
 
     fn read<'a>(&'a mut self) -> impl std::future::Future<Output = Result<Self::Interceptor, Error>> +Send + 'a;
 }
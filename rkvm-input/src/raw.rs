@@ -0,0 +1,9 @@
+use serde::{Deserialize, Serialize};
+
+/// An event rkvm doesn't model, such as `EV_MSC` or a relative axis without a name, passed through as is.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RawEvent {
+    pub r#type: u16,
+    pub code: u16,
+    pub value: i32,
+}
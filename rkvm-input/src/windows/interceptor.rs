@@ -1,13 +1,16 @@
 use crate::interceptor::{InterceptorPlatform,Repeat};
 use crate::abs::{AbsAxis, AbsInfo};
+use crate::device::DeviceInfo;
 use crate::event::Event;
+use crate::ff::{Ff, FfEffect};
 use crate::key::Key;
+use crate::led::{Led, LedEvent};
 use crate::rel::RelAxis;
 
 
 use std::collections::{HashMap, HashSet};
 use std::ffi::CStr;
-use std::io::Error;
+use std::io::{Error, ErrorKind};
 
 pub struct InterceptorWindows;
 
@@ -30,6 +33,9 @@ impl InterceptorPlatform for InterceptorWindows {
     fn version(&self) -> u16 {
         unimplemented!()
     }
+    fn info(&self) -> &DeviceInfo {
+        unimplemented!()
+    }
     fn rel(&self) -> HashSet<RelAxis> {
         unimplemented!()
     }
@@ -42,4 +48,31 @@ impl InterceptorPlatform for InterceptorWindows {
     fn repeat(&self) -> Repeat {
         unimplemented!()
     }
+    fn raw(&self) -> HashSet<(u16, u16)> {
+        HashSet::new()
+    }
+    fn leds(&self) -> HashMap<Led, bool> {
+        HashMap::new()
+    }
+    fn set_led(&mut self, _event: LedEvent) -> Result<(), Error> {
+        Err(ErrorKind::Unsupported.into())
+    }
+    fn ff(&self) -> HashSet<Ff> {
+        HashSet::new()
+    }
+    fn upload_ff(&mut self, _effect: FfEffect) -> Result<i16, Error> {
+        Err(ErrorKind::Unsupported.into())
+    }
+    fn erase_ff(&mut self, _id: i16) -> Result<(), Error> {
+        Err(ErrorKind::Unsupported.into())
+    }
+    fn play_ff(&mut self, _id: i16, _count: i32) -> Result<(), Error> {
+        Err(ErrorKind::Unsupported.into())
+    }
+    fn set_ff_gain(&mut self, _gain: u16) -> Result<(), Error> {
+        Err(ErrorKind::Unsupported.into())
+    }
+    fn set_ff_autocenter(&mut self, _autocenter: u16) -> Result<(), Error> {
+        Err(ErrorKind::Unsupported.into())
+    }
 }
\ No newline at end of file
@@ -11,7 +11,11 @@ pub struct MonitorWindows {
 
 impl MonitorPlatform for MonitorWindows {
     type Interceptor = InterceptorWindows;
-    fn new(_device_allowlist: Vec<DeviceSpec>) -> Self {
+    fn new(
+        _device_allowlist: Vec<DeviceSpec>,
+        _device_denylist: Vec<DeviceSpec>,
+        _switches: bool,
+    ) -> Self {
         let (_sender, receiver) = mpsc::channel(1);
         // tokio::spawn(monitor(sender));
 
@@ -1,13 +1,16 @@
-use crate::writer::{WriterPlatform,WriterBuilderPlatform};
+use crate::writer::{Output, WriterBuilderPlatform, WriterPlatform};
 use crate::abs::{AbsAxis, AbsInfo, AbsEvent};
 use crate::event::Event;
+use crate::ff::Ff;
 use crate::key::{Key, KeyEvent,Keyboard, Button};
+use crate::led::Led;
 use crate::rel::{RelAxis, RelEvent};
 
 use crate::windows::key_repeater::KeyRepeater;
 use crate::windows::normalizer::AxisNormalizer;
 
 use std::ffi::CString;
+use std::future;
 use std::io::Error;
 use std::collections::HashMap;
 use std::time::Duration;
@@ -165,8 +168,12 @@ impl WriterPlatform for WriterWindows {
                 match key {
                     Key::Key(key) => self.key(key, down),
                     Key::Button(button) => self.button(button, down),
+                    Key::Raw(code) => tracing::warn!("Key not handled: {}", code),
                 }
             }
+            // Windows treats repeated key downs as autorepeat.
+            Event::Repeat(Key::Key(key)) => self.key(key, &true),
+            Event::Repeat(_) => {}
             Event::Rel(RelEvent { axis, value }) => {
                 match axis {
                     RelAxis::X => self.mouse_move(false, *value, 0),
@@ -203,11 +210,18 @@ impl WriterPlatform for WriterWindows {
                     _ => tracing::warn!("Abs event not handled: {:?}", event),
                 }
             }
-            Event::Sync(_) => self.flush()?
+            Event::Sync(_) => self.flush()?,
+            // Raw events are Linux specific.
+            Event::Raw(_) => {}
         }
 
         Ok(())
     }
+
+    // Windows doesn't tell us about LED changes and there's no force feedback support.
+    async fn read(&mut self) -> Result<Output, Error> {
+        future::pending().await
+    }
 }
 
 pub struct WriterWindowsBuilder {
@@ -269,6 +283,18 @@ impl WriterBuilderPlatform for WriterWindowsBuilder {
         Ok(self)
     }
 
+    fn led<T: IntoIterator<Item = Led>>(self, _items: T) -> Result<Self, Error> {
+        Ok(self)
+    }
+
+    fn ff<T: IntoIterator<Item = Ff>>(self, _items: T) -> Result<Self, Error> {
+        Ok(self)
+    }
+
+    fn raw<T: IntoIterator<Item = (u16, u16)>>(self, _items: T) -> Result<Self, Error> {
+        Ok(self)
+    }
+
     fn delay(mut self, value: Option<i32>) -> Result<Self, Error> {
         if let Some(delay) = value {
             if delay > 0 {
@@ -1,6 +1,8 @@
 use crate::abs::{AbsAxis, AbsInfo};
 use crate::event::Event;
+use crate::ff::{Ff, FfEvent};
 use crate::key::Key;
+use crate::led::{Led, LedEvent};
 use crate::rel::RelAxis;
 
 use std::ffi::CString;
@@ -11,12 +13,22 @@ pub use {crate::windows::writer::WriterWindows as Writer, crate::windows::writer
 #[cfg(target_os = "linux")]
 pub use {crate::linux::writer::WriterLinux as Writer, crate::linux::writer::WriterLinuxBuilder as WriterBuilder};
 
+/// An event written to a device by the system rather than by us.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Output {
+    Led(LedEvent),
+    Ff(FfEvent),
+}
+
 pub trait WriterPlatform {
     type Builder: WriterBuilderPlatform;
 
     fn builder() -> Result<Self::Builder, Error>;
 
     fn write<'a>(&'a mut self, event: &'a Event) -> impl std::future::Future<Output = Result<(), Error>> + Send + 'a;
+
+    /// Waits for the system to change the state of one of the device's LEDs or to make a force feedback request.
+    fn read(&mut self) -> impl std::future::Future<Output = Result<Output, Error>> + Send + '_;
 }
 
 pub trait WriterBuilderPlatform: Sized {
@@ -36,6 +48,13 @@ pub trait WriterBuilderPlatform: Sized {
 
     fn key<T: IntoIterator<Item = Key>>(self, items: T) -> Result<Self, Error>;
 
+    fn led<T: IntoIterator<Item = Led>>(self, items: T) -> Result<Self, Error>;
+
+    fn ff<T: IntoIterator<Item = Ff>>(self, items: T) -> Result<Self, Error>;
+
+    /// Enables event codes of raw events, as pairs of type and code.
+    fn raw<T: IntoIterator<Item = (u16, u16)>>(self, items: T) -> Result<Self, Error>;
+
     fn delay(self, value: Option<i32>) -> Result<Self, Error>;
 
     fn period(self, value: Option<i32>) -> Result<Self, Error>;
@@ -0,0 +1,57 @@
+//! Protocol of the server's local control socket.
+//!
+//! The client sends a [`Request`] and the server replies with a [`Response`], any number of times
+//! over the same connection.
+
+use serde::{Deserialize, Serialize};
+use std::ffi::CString;
+use std::net::SocketAddr;
+
+#[derive(Deserialize, Serialize, Debug)]
+pub enum Request {
+    Switch(Target),
+    /// Switches to the next connected client, same as the switch keys.
+    Cycle,
+    Status,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub enum Target {
+    Server,
+    /// Same as [`ClientStatus::idx`].
+    Index(usize),
+    Name(String),
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+pub enum Response {
+    Done,
+    Status(Status),
+    Error(String),
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct Status {
+    /// Index of the focused client, 0 being the server itself.
+    pub current: usize,
+    pub clients: Vec<ClientStatus>,
+    pub devices: Vec<DeviceStatus>,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct ClientStatus {
+    pub idx: usize,
+    pub name: Option<String>,
+    /// None if the client is not connected.
+    pub addr: Option<SocketAddr>,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct DeviceStatus {
+    pub id: usize,
+    pub name: CString,
+    pub vendor: u16,
+    pub product: u16,
+    /// Client the device is pinned to, if any.
+    pub pinned: Option<usize>,
+}
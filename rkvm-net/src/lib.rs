@@ -2,19 +2,25 @@
 #![allow(async_fn_in_trait)]
 
 pub mod auth;
+pub mod control;
 pub mod message;
 pub mod version;
 
+use message::Message;
 use rkvm_input::abs::{AbsAxis, AbsInfo};
 use rkvm_input::event::Event;
+use rkvm_input::ff::{Ff, FfEvent};
 use rkvm_input::key::Key;
+use rkvm_input::led::{Led, LedEvent};
 use rkvm_input::rel::RelAxis;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 use std::ffi::CString;
 use std::future::Future;
 use std::io::{Error, ErrorKind};
 use std::time::Duration;
+use tokio::io::AsyncRead;
 use tokio::time;
 
 pub const PING_INTERVAL: Duration = Duration::from_secs(1);
@@ -28,6 +34,8 @@ pub const WRITE_TIMEOUT: Duration = Duration::from_millis(500);
 // TLS negotiation timeout.
 pub const TLS_TIMEOUT: Duration = Duration::from_millis(500);
 
+// Device creation is rare compared to events, boxing it isn't worth it.
+#[allow(clippy::large_enum_variant)]
 #[derive(Deserialize, Serialize, Debug)]
 pub enum Update {
     CreateDevice {
@@ -39,8 +47,14 @@ pub enum Update {
         rel: HashSet<RelAxis>,
         abs: HashMap<AbsAxis, AbsInfo>,
         keys: HashSet<Key>,
+        leds: HashSet<Led>,
+        ff: HashSet<Ff>,
+        /// Codes of raw events, as pairs of type and code.
+        raw: HashSet<(u16, u16)>,
         delay: Option<i32>,
         period: Option<i32>,
+        /// Whether the server sends its own [`Event::Repeat`] events, the device must not repeat keys by itself then.
+        server_repeat: bool,
     },
     DestroyDevice {
         id: usize,
@@ -49,11 +63,44 @@ pub enum Update {
         id: usize,
         event: Event,
     },
+    /// Whether the client is the target input currently goes to, sent on connect and whenever that changes.
+    Focus {
+        active: bool,
+    },
     Ping,
 }
 
+/// Sent by the client after authentication, at any time.
+#[derive(Deserialize, Serialize, Debug)]
+pub enum Feedback {
+    /// Reply to [`Update::Ping`].
+    Pong,
+    /// The client's system changed an LED of the device.
+    Led { id: usize, event: LedEvent },
+    /// The client's system made a force feedback request to the device.
+    Ff { id: usize, event: FfEvent },
+}
+
+/// Sent by the client after the version, before authentication.
 #[derive(Deserialize, Serialize, Debug)]
-pub struct Pong;
+pub struct Hello {
+    /// Identifies the client in the server's config.
+    pub name: String,
+}
+
+/// Decodes a message, handing the stream back along with it.
+///
+/// Unlike [`Message::decode`], the returned future owns the stream, so it can be kept around
+/// and polled in a loop with other futures without losing partially read data.
+pub async fn decode_owned<T, R>(mut stream: R) -> (R, Result<T, Error>)
+where
+    // Bounds of the blanket implementation of Message, so that the future is Send when the stream is.
+    T: DeserializeOwned + Serialize + Sync,
+    R: AsyncRead + Send + Unpin,
+{
+    let result = T::decode(&mut stream).await;
+    (stream, result)
+}
 
 pub async fn timeout<T: Future<Output = Result<U, Error>>, U>(
     duration: Duration,
@@ -66,13 +113,12 @@ pub async fn timeout<T: Future<Output = Result<U, Error>>, U>(
 
 #[cfg(test)]
 mod test {
-    use super::message::Message;
     use super::*;
 
     #[tokio::test]
     async fn pong_is_not_empty() {
         let mut data = Vec::new();
-        Pong.encode(&mut data).await.unwrap();
+        Feedback::Pong.encode(&mut data).await.unwrap();
 
         assert!(!data.is_empty());
     }
@@ -1,8 +1,11 @@
 use rkvm_input::device::DeviceSpec;
 use rkvm_input::key::{Button, Key, Keyboard};
-use serde::Deserialize;
-use std::collections::HashSet;
+use serde::de::value::StrDeserializer;
+use serde::de::IntoDeserializer;
+use serde::{Deserialize, Deserializer};
+use std::collections::{HashMap, HashSet};
 use std::net::{SocketAddr, IpAddr};
+use std::num::NonZeroUsize;
 use std::path::PathBuf;
 
 #[derive(Deserialize)]
@@ -11,21 +14,231 @@ pub struct Config {
     pub listen: SocketAddr,
     pub certificate: PathBuf,
     pub key: PathBuf,
-    pub password: String,
+    pub password: Option<String>,
+    #[serde(default)]
+    pub revoked: HashSet<String>,
     pub switch_keys: HashSet<SwitchKey>,
     pub propagate_switch_keys: Option<bool>,
+    pub carry_modifiers: Option<bool>,
     #[serde(default)]
     pub device_allowlist: Vec<DeviceSpec>,
+    #[serde(default)]
+    pub device_denylist: Vec<DeviceSpec>,
+    pub forward_switch_devices: Option<bool>,
+    pub server_repeat: Option<bool>,
+    #[serde(default)]
+    pub device_routes: Vec<DeviceRouteConfig>,
     pub goto_keys: Option<HashSet<SwitchKey>>,
+    pub wheel_switch_keys: Option<HashSet<SwitchKey>>,
+    #[serde(default)]
+    pub broadcast_groups: Vec<BroadcastGroupConfig>,
+    #[serde(default)]
+    pub macros: Vec<MacroConfig>,
+    #[serde(default)]
+    pub tap_triggers: Vec<TapTriggerConfig>,
     #[serde(default)]
     pub clients: Vec<ClientConfig>,
+    pub layout: Option<LayoutConfig>,
+    pub control_socket: Option<PathBuf>,
+    pub on_switch: Option<String>,
+    pub on_client_connect: Option<String>,
+    pub on_client_disconnect: Option<String>,
+    pub idle_timeout: Option<u64>,
+    pub idle_fallback: Option<String>,
 }
 
 #[derive(Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct ClientConfig {
-    pub addr: IpAddr,
+    pub addr: Option<IpAddr>,
+    pub name: Option<String>,
+    pub password: Option<String>,
     pub goto_keys: Option<HashSet<SwitchKey>>,
+    #[serde(default, deserialize_with = "deserialize_remap")]
+    pub remap: HashMap<SwitchKey, SwitchKey>,
+    pub idle_timeout: Option<u64>,
+    #[serde(default)]
+    pub blocked_keys: HashSet<SwitchKey>,
+    #[serde(default)]
+    pub blocked_chords: Vec<HashSet<SwitchKey>>,
+    pub pointer: Option<PointerConfig>,
+    #[serde(default)]
+    pub abs_transforms: Vec<AbsTransformConfig>,
+}
+
+/// Returns the index of the configured client a connecting client belongs to.
+///
+/// Names take precedence, addresses are only used if no entry has the name,
+/// and only for entries without a name so that other clients can't take them over.
+pub fn find_client(clients: &[ClientConfig], name: &str, addr: IpAddr) -> Option<usize> {
+    clients
+        .iter()
+        .position(|client| client.name.as_deref() == Some(name))
+        .or_else(|| {
+            clients
+                .iter()
+                .position(|client| client.name.is_none() && client.addr == Some(addr))
+        })
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct AbsTransformConfig {
+    #[serde(flatten)]
+    pub device: DeviceSpec,
+    pub rotate: Option<Rotation>,
+    pub swap_xy: Option<bool>,
+    pub invert_x: Option<bool>,
+    pub invert_y: Option<bool>,
+    pub area: Option<Area>,
+}
+
+/// Clockwise rotation in degrees.
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(try_from = "u16")]
+pub enum Rotation {
+    None,
+    Quarter,
+    Half,
+    ThreeQuarters,
+}
+
+impl TryFrom<u16> for Rotation {
+    type Error = String;
+
+    fn try_from(degrees: u16) -> Result<Self, Self::Error> {
+        match degrees {
+            0 => Ok(Self::None),
+            90 => Ok(Self::Quarter),
+            180 => Ok(Self::Half),
+            270 => Ok(Self::ThreeQuarters),
+            _ => Err(format!(
+                "Invalid rotation {}, expected 0, 90, 180 or 270",
+                degrees
+            )),
+        }
+    }
+}
+
+/// Part of the client's range a device is mapped to, as fractions `[left, top, right, bottom]`.
+#[derive(Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(try_from = "[f64; 4]")]
+pub struct Area {
+    pub left: f64,
+    pub top: f64,
+    pub right: f64,
+    pub bottom: f64,
+}
+
+impl TryFrom<[f64; 4]> for Area {
+    type Error = String;
+
+    fn try_from([left, top, right, bottom]: [f64; 4]) -> Result<Self, Self::Error> {
+        let valid = |low: f64, high: f64| 0.0 <= low && low < high && high <= 1.0;
+        if !valid(left, right) || !valid(top, bottom) {
+            return Err(
+                "Invalid area, expected [left, top, right, bottom] within 0 and 1".to_owned(),
+            );
+        }
+
+        Ok(Self {
+            left,
+            top,
+            right,
+            bottom,
+        })
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct PointerConfig {
+    pub sensitivity: Option<f64>,
+    // Extra gain per unit of motion in a single event.
+    pub acceleration: Option<f64>,
+    pub wheel_scale: Option<f64>,
+    pub invert_wheel: Option<bool>,
+    pub invert_hwheel: Option<bool>,
+}
+
+// TOML table keys are always strings, which can't be deserialized into an enum directly.
+// Key codes are strings there too, so they're parsed separately.
+fn deserialize_remap<'de, D>(deserializer: D) -> Result<HashMap<SwitchKey, SwitchKey>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    HashMap::<String, SwitchKey>::deserialize(deserializer)?
+        .into_iter()
+        .map(|(from, to)| {
+            if let Ok(code) = from.parse() {
+                let code = key_code(code).map_err(serde::de::Error::custom)?;
+                return Ok((SwitchKey::Raw(code), to));
+            }
+
+            let from: StrDeserializer<D::Error> = from.as_str().into_deserializer();
+            Ok((SwitchKey::deserialize(from)?, to))
+        })
+        .collect()
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct BroadcastGroupConfig {
+    pub keys: HashSet<SwitchKey>,
+    pub clients: Option<Vec<String>>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct TapTriggerConfig {
+    pub key: SwitchKey,
+    pub count: NonZeroUsize,
+    // Milliseconds.
+    pub window: Option<u64>,
+    pub client: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct MacroConfig {
+    pub keys: HashSet<SwitchKey>,
+    pub steps: Vec<MacroStep>,
+}
+
+#[derive(Deserialize, Clone, Copy)]
+#[serde(rename_all = "kebab-case")]
+pub enum MacroStep {
+    Press(SwitchKey),
+    Release(SwitchKey),
+    // Milliseconds.
+    Sleep(u64),
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct DeviceRouteConfig {
+    #[serde(flatten)]
+    pub device: DeviceSpec,
+    pub client: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct LayoutConfig {
+    pub width: u32,
+    pub height: u32,
+    pub left: Option<NeighbourConfig>,
+    pub right: Option<NeighbourConfig>,
+    pub above: Option<NeighbourConfig>,
+    pub below: Option<NeighbourConfig>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct NeighbourConfig {
+    pub client: String,
+    pub width: u32,
+    pub height: u32,
 }
 
 #[derive(Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
@@ -638,604 +851,624 @@ pub enum SwitchKey {
     TriggerHappy38,
     TriggerHappy39,
     TriggerHappy40,
+    // Linux key code, for keys without a name.
+    #[serde(untagged)]
+    Raw(#[serde(deserialize_with = "deserialize_key_code")] u16),
+}
+
+fn deserialize_key_code<'de, D>(deserializer: D) -> Result<u16, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let code = u16::deserialize(deserializer)?;
+    key_code(code).map_err(serde::de::Error::custom)
+}
+
+fn key_code(code: u16) -> Result<u16, String> {
+    match Key::from_code(code) {
+        Some(_) => Ok(code),
+        None => Err(format!("Invalid key code {}", code)),
+    }
 }
 
-impl Into<Key> for SwitchKey {
-    fn into(self) -> Key {
-        match self {
+impl From<SwitchKey> for Key {
+    fn from(key: SwitchKey) -> Self {
+        match key {
             // Keys.
-            Self::A => Key::Key(Keyboard::A),
-            Self::Ab => Key::Key(Keyboard::Ab),
-            Self::AddressBook => Key::Key(Keyboard::AddressBook),
-            Self::Again => Key::Key(Keyboard::Again),
-            Self::AlsToggle => Key::Key(Keyboard::AlsToggle),
-            Self::AltErase => Key::Key(Keyboard::AltErase),
-            Self::Angle => Key::Key(Keyboard::Angle),
-            Self::Apostrophe => Key::Key(Keyboard::Apostrophe),
-            Self::Appselect => Key::Key(Keyboard::Appselect),
-            Self::Archive => Key::Key(Keyboard::Archive),
-            Self::AspectRatio => Key::Key(Keyboard::AspectRatio),
-            Self::Assistant => Key::Key(Keyboard::Assistant),
-            Self::AttendantOff => Key::Key(Keyboard::AttendantOff),
-            Self::AttendantOn => Key::Key(Keyboard::AttendantOn),
-            Self::AttendantToggle => Key::Key(Keyboard::AttendantToggle),
-            Self::Audio => Key::Key(Keyboard::Audio),
-            Self::AudioDesc => Key::Key(Keyboard::AudioDesc),
-            Self::Aux => Key::Key(Keyboard::Aux),
-            Self::B => Key::Key(Keyboard::B),
-            Self::Back => Key::Key(Keyboard::Back),
-            Self::Backslash => Key::Key(Keyboard::Backslash),
-            Self::Backspace => Key::Key(Keyboard::Backspace),
-            Self::BassBoost => Key::Key(Keyboard::BassBoost),
-            Self::Battery => Key::Key(Keyboard::Battery),
-            Self::Blue => Key::Key(Keyboard::Blue),
-            Self::Bluetooth => Key::Key(Keyboard::Bluetooth),
-            Self::Bookmarks => Key::Key(Keyboard::Bookmarks),
-            Self::Break => Key::Key(Keyboard::Break),
-            Self::BrightnessAuto => Key::Key(Keyboard::BrightnessAuto),
-            Self::BrightnessCycle => Key::Key(Keyboard::BrightnessCycle),
-            Self::BrightnessMax => Key::Key(Keyboard::BrightnessMax),
-            Self::BrightnessMin => Key::Key(Keyboard::BrightnessMin),
-            Self::BrightnessToggle => Key::Key(Keyboard::BrightnessToggle),
-            Self::BrightnessDown => Key::Key(Keyboard::BrightnessDown),
-            Self::BrightnessUp => Key::Key(Keyboard::BrightnessUp),
-            Self::BrlDot1 => Key::Key(Keyboard::BrlDot1),
-            Self::BrlDot10 => Key::Key(Keyboard::BrlDot10),
-            Self::BrlDot2 => Key::Key(Keyboard::BrlDot2),
-            Self::BrlDot3 => Key::Key(Keyboard::BrlDot3),
-            Self::BrlDot4 => Key::Key(Keyboard::BrlDot4),
-            Self::BrlDot5 => Key::Key(Keyboard::BrlDot5),
-            Self::BrlDot6 => Key::Key(Keyboard::BrlDot6),
-            Self::BrlDot7 => Key::Key(Keyboard::BrlDot7),
-            Self::BrlDot8 => Key::Key(Keyboard::BrlDot8),
-            Self::BrlDot9 => Key::Key(Keyboard::BrlDot9),
-            Self::ButtonConfig => Key::Key(Keyboard::ButtonConfig),
-            Self::C => Key::Key(Keyboard::C),
-            Self::Calc => Key::Key(Keyboard::Calc),
-            Self::Calendar => Key::Key(Keyboard::Calendar),
-            Self::Camera => Key::Key(Keyboard::Camera),
-            Self::CameraDown => Key::Key(Keyboard::CameraDown),
-            Self::CameraFocus => Key::Key(Keyboard::CameraFocus),
-            Self::CameraLeft => Key::Key(Keyboard::CameraLeft),
-            Self::CameraRight => Key::Key(Keyboard::CameraRight),
-            Self::CameraUp => Key::Key(Keyboard::CameraUp),
-            Self::CameraZoomIn => Key::Key(Keyboard::CameraZoomIn),
-            Self::CameraZoomOut => Key::Key(Keyboard::CameraZoomOut),
-            Self::Cancel => Key::Key(Keyboard::Cancel),
-            Self::CapsLock => Key::Key(Keyboard::CapsLock),
-            Self::Cd => Key::Key(Keyboard::Cd),
-            Self::Channel => Key::Key(Keyboard::Channel),
-            Self::ChannelDown => Key::Key(Keyboard::ChannelDown),
-            Self::ChannelUp => Key::Key(Keyboard::ChannelUp),
-            Self::Chat => Key::Key(Keyboard::Chat),
-            Self::Clear => Key::Key(Keyboard::Clear),
-            Self::Close => Key::Key(Keyboard::Close),
-            Self::CloseCd => Key::Key(Keyboard::CloseCd),
-            Self::Coffee => Key::Key(Keyboard::Coffee),
-            Self::Comma => Key::Key(Keyboard::Comma),
-            Self::Compose => Key::Key(Keyboard::Compose),
-            Self::Computer => Key::Key(Keyboard::Computer),
-            Self::Config => Key::Key(Keyboard::Config),
-            Self::Connect => Key::Key(Keyboard::Connect),
-            Self::ContextMenu => Key::Key(Keyboard::ContextMenu),
-            Self::Controlpanel => Key::Key(Keyboard::Controlpanel),
-            Self::Copy => Key::Key(Keyboard::Copy),
-            Self::Cut => Key::Key(Keyboard::Cut),
-            Self::CycleWindows => Key::Key(Keyboard::CycleWindows),
-            Self::D => Key::Key(Keyboard::D),
-            Self::Dashboard => Key::Key(Keyboard::Dashboard),
-            Self::Data => Key::Key(Keyboard::Data),
-            Self::Database => Key::Key(Keyboard::Database),
-            Self::DelEol => Key::Key(Keyboard::DelEol),
-            Self::DelEos => Key::Key(Keyboard::DelEos),
-            Self::DelLine => Key::Key(Keyboard::DelLine),
-            Self::Delete => Key::Key(Keyboard::Delete),
-            Self::DeleteFile => Key::Key(Keyboard::DeleteFile),
-            Self::Digits => Key::Key(Keyboard::Digits),
-            Self::Directory => Key::Key(Keyboard::Directory),
-            Self::DisplayOff => Key::Key(Keyboard::DisplayOff),
-            Self::DisplayToggle => Key::Key(Keyboard::DisplayToggle),
-            Self::Documents => Key::Key(Keyboard::Documents),
-            Self::Dollar => Key::Key(Keyboard::Dollar),
-            Self::Dot => Key::Key(Keyboard::Dot),
-            Self::Down => Key::Key(Keyboard::Down),
-            Self::Dvd => Key::Key(Keyboard::Dvd),
-            Self::E => Key::Key(Keyboard::E),
-            Self::Edit => Key::Key(Keyboard::Edit),
-            Self::Editor => Key::Key(Keyboard::Editor),
-            Self::EjectCd => Key::Key(Keyboard::EjectCd),
-            Self::EjectCloseCd => Key::Key(Keyboard::EjectCloseCd),
-            Self::Email => Key::Key(Keyboard::Email),
-            Self::End => Key::Key(Keyboard::End),
-            Self::Enter => Key::Key(Keyboard::Enter),
-            Self::Epg => Key::Key(Keyboard::Epg),
-            Self::Equal => Key::Key(Keyboard::Equal),
-            Self::Esc => Key::Key(Keyboard::Esc),
-            Self::Euro => Key::Key(Keyboard::Euro),
-            Self::Exit => Key::Key(Keyboard::Exit),
-            Self::F => Key::Key(Keyboard::F),
-            Self::F1 => Key::Key(Keyboard::F1),
-            Self::F10 => Key::Key(Keyboard::F10),
-            Self::F11 => Key::Key(Keyboard::F11),
-            Self::F12 => Key::Key(Keyboard::F12),
-            Self::F13 => Key::Key(Keyboard::F13),
-            Self::F14 => Key::Key(Keyboard::F14),
-            Self::F15 => Key::Key(Keyboard::F15),
-            Self::F16 => Key::Key(Keyboard::F16),
-            Self::F17 => Key::Key(Keyboard::F17),
-            Self::F18 => Key::Key(Keyboard::F18),
-            Self::F19 => Key::Key(Keyboard::F19),
-            Self::F2 => Key::Key(Keyboard::F2),
-            Self::F20 => Key::Key(Keyboard::F20),
-            Self::F21 => Key::Key(Keyboard::F21),
-            Self::F22 => Key::Key(Keyboard::F22),
-            Self::F23 => Key::Key(Keyboard::F23),
-            Self::F24 => Key::Key(Keyboard::F24),
-            Self::F3 => Key::Key(Keyboard::F3),
-            Self::F4 => Key::Key(Keyboard::F4),
-            Self::F5 => Key::Key(Keyboard::F5),
-            Self::F6 => Key::Key(Keyboard::F6),
-            Self::F7 => Key::Key(Keyboard::F7),
-            Self::F8 => Key::Key(Keyboard::F8),
-            Self::F9 => Key::Key(Keyboard::F9),
-            Self::FastForward => Key::Key(Keyboard::FastForward),
-            Self::FastReverse => Key::Key(Keyboard::FastReverse),
-            Self::Favorites => Key::Key(Keyboard::Favorites),
-            Self::File => Key::Key(Keyboard::File),
-            Self::Finance => Key::Key(Keyboard::Finance),
-            Self::Find => Key::Key(Keyboard::Find),
-            Self::First => Key::Key(Keyboard::First),
-            Self::Fn => Key::Key(Keyboard::Fn),
-            Self::Fn1 => Key::Key(Keyboard::Fn1),
-            Self::Fn2 => Key::Key(Keyboard::Fn2),
-            Self::FnB => Key::Key(Keyboard::FnB),
-            Self::FnD => Key::Key(Keyboard::FnD),
-            Self::FnE => Key::Key(Keyboard::FnE),
-            Self::FnEsc => Key::Key(Keyboard::FnEsc),
-            Self::FnF => Key::Key(Keyboard::FnF),
-            Self::FnF1 => Key::Key(Keyboard::FnF1),
-            Self::FnF10 => Key::Key(Keyboard::FnF10),
-            Self::FnF11 => Key::Key(Keyboard::FnF11),
-            Self::FnF12 => Key::Key(Keyboard::FnF12),
-            Self::FnF2 => Key::Key(Keyboard::FnF2),
-            Self::FnF3 => Key::Key(Keyboard::FnF3),
-            Self::FnF4 => Key::Key(Keyboard::FnF4),
-            Self::FnF5 => Key::Key(Keyboard::FnF5),
-            Self::FnF6 => Key::Key(Keyboard::FnF6),
-            Self::FnF7 => Key::Key(Keyboard::FnF7),
-            Self::FnF8 => Key::Key(Keyboard::FnF8),
-            Self::FnF9 => Key::Key(Keyboard::FnF9),
-            Self::FnS => Key::Key(Keyboard::FnS),
-            Self::Forward => Key::Key(Keyboard::Forward),
-            Self::ForwardMail => Key::Key(Keyboard::ForwardMail),
-            Self::Frameback => Key::Key(Keyboard::Frameback),
-            Self::FrameForward => Key::Key(Keyboard::FrameForward),
-            Self::Front => Key::Key(Keyboard::Front),
-            Self::FullScreen => Key::Key(Keyboard::FullScreen),
-            Self::G => Key::Key(Keyboard::G),
-            Self::Games => Key::Key(Keyboard::Games),
-            Self::Goto => Key::Key(Keyboard::Goto),
-            Self::GraphicsEditor => Key::Key(Keyboard::GraphicsEditor),
-            Self::Grave => Key::Key(Keyboard::Grave),
-            Self::Green => Key::Key(Keyboard::Green),
-            Self::H => Key::Key(Keyboard::H),
-            Self::Hangeul => Key::Key(Keyboard::Hangeul),
-            Self::Hanja => Key::Key(Keyboard::Hanja),
-            Self::Help => Key::Key(Keyboard::Help),
-            Self::Henkan => Key::Key(Keyboard::Henkan),
-            Self::Hiragana => Key::Key(Keyboard::Hiragana),
-            Self::Home => Key::Key(Keyboard::Home),
-            Self::Homepage => Key::Key(Keyboard::Homepage),
-            Self::Hp => Key::Key(Keyboard::Hp),
-            Self::I => Key::Key(Keyboard::I),
-            Self::Images => Key::Key(Keyboard::Images),
-            Self::Info => Key::Key(Keyboard::Info),
-            Self::InsLine => Key::Key(Keyboard::InsLine),
-            Self::Insert => Key::Key(Keyboard::Insert),
-            Self::Iso => Key::Key(Keyboard::Iso),
-            Self::J => Key::Key(Keyboard::J),
-            Self::Journal => Key::Key(Keyboard::Journal),
-            Self::K => Key::Key(Keyboard::K),
-            Self::Katakana => Key::Key(Keyboard::Katakana),
-            Self::KatakanaHiragana => Key::Key(Keyboard::KatakanaHiragana),
-            Self::KbdLayoutNext => Key::Key(Keyboard::KbdLayoutNext),
-            Self::KbdLcdMenu1 => Key::Key(Keyboard::KbdLcdMenu1),
-            Self::KbdLcdMenu2 => Key::Key(Keyboard::KbdLcdMenu2),
-            Self::KbdLcdMenu3 => Key::Key(Keyboard::KbdLcdMenu3),
-            Self::KbdLcdMenu4 => Key::Key(Keyboard::KbdLcdMenu4),
-            Self::KbdLcdMenu5 => Key::Key(Keyboard::KbdLcdMenu5),
-            Self::KbdIllumDown => Key::Key(Keyboard::KbdIllumDown),
-            Self::KbdIllumToggle => Key::Key(Keyboard::KbdIllumToggle),
-            Self::KbdIllumUp => Key::Key(Keyboard::KbdIllumUp),
-            Self::KbdInputAssistAccept => Key::Key(Keyboard::KbdInputAssistAccept),
-            Self::KbdInputAssistCancel => Key::Key(Keyboard::KbdInputAssistCancel),
-            Self::KbdInputAssistNext => Key::Key(Keyboard::KbdInputAssistNext),
-            Self::KbdInputAssistNextgroup => Key::Key(Keyboard::KbdInputAssistNextgroup),
-            Self::KbdInputAssistPrev => Key::Key(Keyboard::KbdInputAssistPrev),
-            Self::KbdInputAssistPrevgroup => Key::Key(Keyboard::KbdInputAssistPrevgroup),
-            Self::Keyboard => Key::Key(Keyboard::Keyboard),
-            Self::Kp0 => Key::Key(Keyboard::Kp0),
-            Self::Kp1 => Key::Key(Keyboard::Kp1),
-            Self::Kp2 => Key::Key(Keyboard::Kp2),
-            Self::Kp3 => Key::Key(Keyboard::Kp3),
-            Self::Kp4 => Key::Key(Keyboard::Kp4),
-            Self::Kp5 => Key::Key(Keyboard::Kp5),
-            Self::Kp6 => Key::Key(Keyboard::Kp6),
-            Self::Kp7 => Key::Key(Keyboard::Kp7),
-            Self::Kp8 => Key::Key(Keyboard::Kp8),
-            Self::Kp9 => Key::Key(Keyboard::Kp9),
-            Self::KpAsterisk => Key::Key(Keyboard::KpAsterisk),
-            Self::KpComma => Key::Key(Keyboard::KpComma),
-            Self::KpDott => Key::Key(Keyboard::KpDot),
-            Self::KpEnter => Key::Key(Keyboard::KpEnter),
-            Self::KpEqual => Key::Key(Keyboard::KpEqual),
-            Self::KpJpComma => Key::Key(Keyboard::KpJpComma),
-            Self::KpLeftParen => Key::Key(Keyboard::KpLeftParen),
-            Self::KpMinus => Key::Key(Keyboard::KpMinus),
-            Self::KpPlus => Key::Key(Keyboard::KpPlus),
-            Self::KpPlusMinus => Key::Key(Keyboard::KpPlusMinus),
-            Self::KpRightParen => Key::Key(Keyboard::KpRightParen),
-            Self::KpSlash => Key::Key(Keyboard::KpSlash),
-            Self::L => Key::Key(Keyboard::L),
-            Self::Language => Key::Key(Keyboard::Language),
-            Self::Last => Key::Key(Keyboard::Last),
-            Self::Left => Key::Key(Keyboard::Left),
-            Self::LeftDown => Key::Key(Keyboard::LeftDown),
-            Self::LeftUp => Key::Key(Keyboard::LeftUp),
-            Self::LeftAlt => Key::Key(Keyboard::LeftAlt),
-            Self::LeftBrace => Key::Key(Keyboard::LeftBrace),
-            Self::LeftCtrl => Key::Key(Keyboard::LeftCtrl),
-            Self::LeftMeta => Key::Key(Keyboard::LeftMeta),
-            Self::LeftShift => Key::Key(Keyboard::LeftShift),
-            Self::LightsToggle => Key::Key(Keyboard::LightsToggle),
-            Self::LineFeed => Key::Key(Keyboard::LineFeed),
-            Self::List => Key::Key(Keyboard::List),
-            Self::LogOff => Key::Key(Keyboard::LogOff),
-            Self::M => Key::Key(Keyboard::M),
-            Self::Macro => Key::Key(Keyboard::Macro),
-            Self::Macro1 => Key::Key(Keyboard::Macro1),
-            Self::Macro10 => Key::Key(Keyboard::Macro10),
-            Self::Macro11 => Key::Key(Keyboard::Macro11),
-            Self::Macro12 => Key::Key(Keyboard::Macro12),
-            Self::Macro13 => Key::Key(Keyboard::Macro13),
-            Self::Macro14 => Key::Key(Keyboard::Macro14),
-            Self::Macro15 => Key::Key(Keyboard::Macro15),
-            Self::Macro16 => Key::Key(Keyboard::Macro16),
-            Self::Macro17 => Key::Key(Keyboard::Macro17),
-            Self::Macro18 => Key::Key(Keyboard::Macro18),
-            Self::Macro19 => Key::Key(Keyboard::Macro19),
-            Self::Macro2 => Key::Key(Keyboard::Macro2),
-            Self::Macro20 => Key::Key(Keyboard::Macro20),
-            Self::Macro21 => Key::Key(Keyboard::Macro21),
-            Self::Macro22 => Key::Key(Keyboard::Macro22),
-            Self::Macro23 => Key::Key(Keyboard::Macro23),
-            Self::Macro24 => Key::Key(Keyboard::Macro24),
-            Self::Macro25 => Key::Key(Keyboard::Macro25),
-            Self::Macro26 => Key::Key(Keyboard::Macro26),
-            Self::Macro27 => Key::Key(Keyboard::Macro27),
-            Self::Macro28 => Key::Key(Keyboard::Macro28),
-            Self::Macro29 => Key::Key(Keyboard::Macro29),
-            Self::Macro3 => Key::Key(Keyboard::Macro3),
-            Self::Macro30 => Key::Key(Keyboard::Macro30),
-            Self::Macro4 => Key::Key(Keyboard::Macro4),
-            Self::Macro5 => Key::Key(Keyboard::Macro5),
-            Self::Macro6 => Key::Key(Keyboard::Macro6),
-            Self::Macro7 => Key::Key(Keyboard::Macro7),
-            Self::Macro8 => Key::Key(Keyboard::Macro8),
-            Self::Macro9 => Key::Key(Keyboard::Macro9),
-            Self::MacroPreset1 => Key::Key(Keyboard::MacroPreset1),
-            Self::MacroPreset2 => Key::Key(Keyboard::MacroPreset2),
-            Self::MacroPreset3 => Key::Key(Keyboard::MacroPreset3),
-            Self::MacroPresetCycle => Key::Key(Keyboard::MacroPresetCycle),
-            Self::MacroRecordStart => Key::Key(Keyboard::MacroRecordStart),
-            Self::MacroRecordStop => Key::Key(Keyboard::MacroRecordStop),
-            Self::Mail => Key::Key(Keyboard::Mail),
-            Self::Media => Key::Key(Keyboard::Media),
-            Self::MediaRepeat => Key::Key(Keyboard::MediaRepeat),
-            Self::MediaTopMenu => Key::Key(Keyboard::MediaTopMenu),
-            Self::Memo => Key::Key(Keyboard::Memo),
-            Self::Menu => Key::Key(Keyboard::Menu),
-            Self::Messenger => Key::Key(Keyboard::Messenger),
-            Self::Mhp => Key::Key(Keyboard::Mhp),
-            Self::MicMute => Key::Key(Keyboard::MicMute),
-            Self::Minus => Key::Key(Keyboard::Minus),
-            Self::Mode => Key::Key(Keyboard::Mode),
-            Self::Move => Key::Key(Keyboard::Move),
-            Self::Mp3 => Key::Key(Keyboard::Mp3),
-            Self::MsDos => Key::Key(Keyboard::MsDos),
-            Self::Muhenkan => Key::Key(Keyboard::Muhenkan),
-            Self::Mute => Key::Key(Keyboard::Mute),
-            Self::N => Key::Key(Keyboard::N),
-            Self::N0 => Key::Key(Keyboard::N0),
-            Self::N1 => Key::Key(Keyboard::N1),
-            Self::N102nd => Key::Key(Keyboard::N102nd),
-            Self::N10ChannelsDown => Key::Key(Keyboard::N10ChannelsDown),
-            Self::N10ChannelsUp => Key::Key(Keyboard::N10ChannelsUp),
-            Self::N2 => Key::Key(Keyboard::N2),
-            Self::N3 => Key::Key(Keyboard::N3),
-            Self::N3dMode => Key::Key(Keyboard::N3dMode),
-            Self::N4 => Key::Key(Keyboard::N4),
-            Self::N5 => Key::Key(Keyboard::N5),
-            Self::N6 => Key::Key(Keyboard::N6),
-            Self::N7 => Key::Key(Keyboard::N7),
-            Self::N8 => Key::Key(Keyboard::N8),
-            Self::N9 => Key::Key(Keyboard::N9),
-            Self::New => Key::Key(Keyboard::New),
-            Self::News => Key::Key(Keyboard::News),
-            Self::Next => Key::Key(Keyboard::Next),
-            Self::NextFavorite => Key::Key(Keyboard::NextFavorite),
-            Self::NextSong => Key::Key(Keyboard::NextSong),
-            Self::Numeric0 => Key::Key(Keyboard::Numeric0),
-            Self::Numeric1 => Key::Key(Keyboard::Numeric1),
-            Self::Numeric11 => Key::Key(Keyboard::Numeric11),
-            Self::Numeric12 => Key::Key(Keyboard::Numeric12),
-            Self::Numeric2 => Key::Key(Keyboard::Numeric2),
-            Self::Numeric3 => Key::Key(Keyboard::Numeric3),
-            Self::Numeric4 => Key::Key(Keyboard::Numeric4),
-            Self::Numeric5 => Key::Key(Keyboard::Numeric5),
-            Self::Numeric6 => Key::Key(Keyboard::Numeric6),
-            Self::Numeric7 => Key::Key(Keyboard::Numeric7),
-            Self::Numeric8 => Key::Key(Keyboard::Numeric8),
-            Self::Numeric9 => Key::Key(Keyboard::Numeric9),
-            Self::NumericA => Key::Key(Keyboard::NumericA),
-            Self::NumericB => Key::Key(Keyboard::NumericB),
-            Self::NumericC => Key::Key(Keyboard::NumericC),
-            Self::NumericD => Key::Key(Keyboard::NumericD),
-            Self::NumericPound => Key::Key(Keyboard::NumericPound),
-            Self::NumericStar => Key::Key(Keyboard::NumericStar),
-            Self::NumLock => Key::Key(Keyboard::NumLock),
-            Self::O => Key::Key(Keyboard::O),
-            Self::Ok => Key::Key(Keyboard::Ok),
-            Self::OnscreenKeyboard => Key::Key(Keyboard::OnscreenKeyboard),
-            Self::Open => Key::Key(Keyboard::Open),
-            Self::Option => Key::Key(Keyboard::Option),
-            Self::P => Key::Key(Keyboard::P),
-            Self::PageDown => Key::Key(Keyboard::PageDown),
-            Self::PageUp => Key::Key(Keyboard::PageUp),
-            Self::Paste => Key::Key(Keyboard::Paste),
-            Self::Pause => Key::Key(Keyboard::Pause),
-            Self::PauseRecord => Key::Key(Keyboard::PauseRecord),
-            Self::PauseCd => Key::Key(Keyboard::PauseCd),
-            Self::Pc => Key::Key(Keyboard::Pc),
-            Self::Phone => Key::Key(Keyboard::Phone),
-            Self::Play => Key::Key(Keyboard::Play),
-            Self::PlayCd => Key::Key(Keyboard::PlayCd),
-            Self::Player => Key::Key(Keyboard::Player),
-            Self::PlayPause => Key::Key(Keyboard::PlayPause),
-            Self::Power => Key::Key(Keyboard::Power),
-            Self::Power2 => Key::Key(Keyboard::Power2),
-            Self::Presentation => Key::Key(Keyboard::Presentation),
-            Self::Previous => Key::Key(Keyboard::Previous),
-            Self::PreviousSong => Key::Key(Keyboard::PreviousSong),
-            Self::Print => Key::Key(Keyboard::Print),
-            Self::PrivacyScreenToggle => Key::Key(Keyboard::PrivacyScreenToggle),
-            Self::Prog1 => Key::Key(Keyboard::Prog1),
-            Self::Prog2 => Key::Key(Keyboard::Prog2),
-            Self::Prog3 => Key::Key(Keyboard::Prog3),
-            Self::Prog4 => Key::Key(Keyboard::Prog4),
-            Self::Program => Key::Key(Keyboard::Program),
-            Self::Props => Key::Key(Keyboard::Props),
-            Self::Pvr => Key::Key(Keyboard::Pvr),
-            Self::Q => Key::Key(Keyboard::Q),
-            Self::Question => Key::Key(Keyboard::Question),
-            Self::R => Key::Key(Keyboard::R),
-            Self::Radio => Key::Key(Keyboard::Radio),
-            Self::Record => Key::Key(Keyboard::Record),
-            Self::Red => Key::Key(Keyboard::Red),
-            Self::Redo => Key::Key(Keyboard::Redo),
-            Self::Refresh => Key::Key(Keyboard::Refresh),
-            Self::Reply => Key::Key(Keyboard::Reply),
-            Self::Reserved => Key::Key(Keyboard::Reserved),
-            Self::Restart => Key::Key(Keyboard::Restart),
-            Self::Rewind => Key::Key(Keyboard::Rewind),
-            Self::RfKill => Key::Key(Keyboard::RfKill),
-            Self::Right => Key::Key(Keyboard::Right),
-            Self::RightDown => Key::Key(Keyboard::RightDown),
-            Self::RightUp => Key::Key(Keyboard::RightUp),
-            Self::RightAlt => Key::Key(Keyboard::RightAlt),
-            Self::RightBrace => Key::Key(Keyboard::RightBrace),
-            Self::RightCtrl => Key::Key(Keyboard::RightCtrl),
-            Self::RightMeta => Key::Key(Keyboard::RightMeta),
-            Self::RightShift => Key::Key(Keyboard::RightShift),
-            Self::Ro => Key::Key(Keyboard::Ro),
-            Self::RootMenu => Key::Key(Keyboard::RootMenu),
-            Self::RotateDisplay => Key::Key(Keyboard::RotateDisplay),
-            Self::RotateLockToggle => Key::Key(Keyboard::RotateLockToggle),
-            Self::S => Key::Key(Keyboard::S),
-            Self::Sat => Key::Key(Keyboard::Sat),
-            Self::Sat2 => Key::Key(Keyboard::Sat2),
-            Self::Save => Key::Key(Keyboard::Save),
-            Self::Scale => Key::Key(Keyboard::Scale),
-            Self::Screensaver => Key::Key(Keyboard::Screensaver),
-            Self::ScrollDown => Key::Key(Keyboard::ScrollDown),
-            Self::ScrollLock => Key::Key(Keyboard::ScrollLock),
-            Self::ScrollUp => Key::Key(Keyboard::ScrollUp),
-            Self::Search => Key::Key(Keyboard::Search),
-            Self::Select => Key::Key(Keyboard::Select),
-            Self::SelectiveScreenshot => Key::Key(Keyboard::SelectiveScreenshot),
-            Self::Semicolon => Key::Key(Keyboard::Semicolon),
-            Self::Send => Key::Key(Keyboard::Send),
-            Self::SendFile => Key::Key(Keyboard::SendFile),
-            Self::Setup => Key::Key(Keyboard::Setup),
-            Self::Shop => Key::Key(Keyboard::Shop),
-            Self::Shuffle => Key::Key(Keyboard::Shuffle),
-            Self::Slash => Key::Key(Keyboard::Slash),
-            Self::Sleep => Key::Key(Keyboard::Sleep),
-            Self::Slow => Key::Key(Keyboard::Slow),
-            Self::SlowReverse => Key::Key(Keyboard::SlowReverse),
-            Self::Sound => Key::Key(Keyboard::Sound),
-            Self::Space => Key::Key(Keyboard::Space),
-            Self::Spellcheck => Key::Key(Keyboard::Spellcheck),
-            Self::Sport => Key::Key(Keyboard::Sport),
-            Self::Spreadsheet => Key::Key(Keyboard::Spreadsheet),
-            Self::Stop => Key::Key(Keyboard::Stop),
-            Self::StopRecord => Key::Key(Keyboard::StopRecord),
-            Self::StopCd => Key::Key(Keyboard::StopCd),
-            Self::Subtitle => Key::Key(Keyboard::Subtitle),
-            Self::Suspend => Key::Key(Keyboard::Suspend),
-            Self::SwitchVideoMode => Key::Key(Keyboard::SwitchVideoMode),
-            Self::SysRq => Key::Key(Keyboard::SysRq),
-            Self::T => Key::Key(Keyboard::T),
-            Self::Tab => Key::Key(Keyboard::Tab),
-            Self::Tape => Key::Key(Keyboard::Tape),
-            Self::TaskManager => Key::Key(Keyboard::TaskManager),
-            Self::Teen => Key::Key(Keyboard::Teen),
-            Self::Text => Key::Key(Keyboard::Text),
-            Self::Time => Key::Key(Keyboard::Time),
-            Self::Title => Key::Key(Keyboard::Title),
-            Self::TouchpadOff => Key::Key(Keyboard::TouchpadOff),
-            Self::TouchpadOn => Key::Key(Keyboard::TouchpadOn),
-            Self::TouchpadToggle => Key::Key(Keyboard::TouchpadToggle),
-            Self::Tuner => Key::Key(Keyboard::Tuner),
-            Self::Tv => Key::Key(Keyboard::Tv),
-            Self::Tv2 => Key::Key(Keyboard::Tv2),
-            Self::Twen => Key::Key(Keyboard::Twen),
-            Self::U => Key::Key(Keyboard::U),
-            Self::Undo => Key::Key(Keyboard::Undo),
-            Self::Unknown => Key::Key(Keyboard::Unknown),
-            Self::Unmute => Key::Key(Keyboard::Unmute),
-            Self::Up => Key::Key(Keyboard::Up),
-            Self::Uwb => Key::Key(Keyboard::Uwb),
-            Self::V => Key::Key(Keyboard::V),
-            Self::Vcr => Key::Key(Keyboard::Vcr),
-            Self::Vcr2 => Key::Key(Keyboard::Vcr2),
-            Self::Vendor => Key::Key(Keyboard::Vendor),
-            Self::Video => Key::Key(Keyboard::Video),
-            Self::VideoNext => Key::Key(Keyboard::VideoNext),
-            Self::VideoPrev => Key::Key(Keyboard::VideoPrev),
-            Self::VideoPhone => Key::Key(Keyboard::VideoPhone),
-            Self::Vod => Key::Key(Keyboard::Vod),
-            Self::VoiceCommand => Key::Key(Keyboard::VoiceCommand),
-            Self::VoiceMail => Key::Key(Keyboard::VoiceMail),
-            Self::VolumeDown => Key::Key(Keyboard::VolumeDown),
-            Self::VolumeUp => Key::Key(Keyboard::VolumeUp),
-            Self::W => Key::Key(Keyboard::W),
-            Self::WakeUp => Key::Key(Keyboard::WakeUp),
-            Self::Wlan => Key::Key(Keyboard::Wlan),
-            Self::WordProcessor => Key::Key(Keyboard::WordProcessor),
-            Self::WpsButton => Key::Key(Keyboard::WpsButton),
-            Self::Wwan => Key::Key(Keyboard::Wwan),
-            Self::Www => Key::Key(Keyboard::Www),
-            Self::X => Key::Key(Keyboard::X),
-            Self::Xfer => Key::Key(Keyboard::Xfer),
-            Self::Y => Key::Key(Keyboard::Y),
-            Self::Yellow => Key::Key(Keyboard::Yellow),
-            Self::Yen => Key::Key(Keyboard::Yen),
-            Self::Z => Key::Key(Keyboard::Z),
-            Self::ZenkakuHankaku => Key::Key(Keyboard::ZenkakuHankaku),
-            Self::ZoomIn => Key::Key(Keyboard::ZoomIn),
-            Self::ZoomOut => Key::Key(Keyboard::ZoomOut),
-            Self::ZoomReset => Key::Key(Keyboard::ZoomReset),
+            SwitchKey::A => Key::Key(Keyboard::A),
+            SwitchKey::Ab => Key::Key(Keyboard::Ab),
+            SwitchKey::AddressBook => Key::Key(Keyboard::AddressBook),
+            SwitchKey::Again => Key::Key(Keyboard::Again),
+            SwitchKey::AlsToggle => Key::Key(Keyboard::AlsToggle),
+            SwitchKey::AltErase => Key::Key(Keyboard::AltErase),
+            SwitchKey::Angle => Key::Key(Keyboard::Angle),
+            SwitchKey::Apostrophe => Key::Key(Keyboard::Apostrophe),
+            SwitchKey::Appselect => Key::Key(Keyboard::Appselect),
+            SwitchKey::Archive => Key::Key(Keyboard::Archive),
+            SwitchKey::AspectRatio => Key::Key(Keyboard::AspectRatio),
+            SwitchKey::Assistant => Key::Key(Keyboard::Assistant),
+            SwitchKey::AttendantOff => Key::Key(Keyboard::AttendantOff),
+            SwitchKey::AttendantOn => Key::Key(Keyboard::AttendantOn),
+            SwitchKey::AttendantToggle => Key::Key(Keyboard::AttendantToggle),
+            SwitchKey::Audio => Key::Key(Keyboard::Audio),
+            SwitchKey::AudioDesc => Key::Key(Keyboard::AudioDesc),
+            SwitchKey::Aux => Key::Key(Keyboard::Aux),
+            SwitchKey::B => Key::Key(Keyboard::B),
+            SwitchKey::Back => Key::Key(Keyboard::Back),
+            SwitchKey::Backslash => Key::Key(Keyboard::Backslash),
+            SwitchKey::Backspace => Key::Key(Keyboard::Backspace),
+            SwitchKey::BassBoost => Key::Key(Keyboard::BassBoost),
+            SwitchKey::Battery => Key::Key(Keyboard::Battery),
+            SwitchKey::Blue => Key::Key(Keyboard::Blue),
+            SwitchKey::Bluetooth => Key::Key(Keyboard::Bluetooth),
+            SwitchKey::Bookmarks => Key::Key(Keyboard::Bookmarks),
+            SwitchKey::Break => Key::Key(Keyboard::Break),
+            SwitchKey::BrightnessAuto => Key::Key(Keyboard::BrightnessAuto),
+            SwitchKey::BrightnessCycle => Key::Key(Keyboard::BrightnessCycle),
+            SwitchKey::BrightnessMax => Key::Key(Keyboard::BrightnessMax),
+            SwitchKey::BrightnessMin => Key::Key(Keyboard::BrightnessMin),
+            SwitchKey::BrightnessToggle => Key::Key(Keyboard::BrightnessToggle),
+            SwitchKey::BrightnessDown => Key::Key(Keyboard::BrightnessDown),
+            SwitchKey::BrightnessUp => Key::Key(Keyboard::BrightnessUp),
+            SwitchKey::BrlDot1 => Key::Key(Keyboard::BrlDot1),
+            SwitchKey::BrlDot10 => Key::Key(Keyboard::BrlDot10),
+            SwitchKey::BrlDot2 => Key::Key(Keyboard::BrlDot2),
+            SwitchKey::BrlDot3 => Key::Key(Keyboard::BrlDot3),
+            SwitchKey::BrlDot4 => Key::Key(Keyboard::BrlDot4),
+            SwitchKey::BrlDot5 => Key::Key(Keyboard::BrlDot5),
+            SwitchKey::BrlDot6 => Key::Key(Keyboard::BrlDot6),
+            SwitchKey::BrlDot7 => Key::Key(Keyboard::BrlDot7),
+            SwitchKey::BrlDot8 => Key::Key(Keyboard::BrlDot8),
+            SwitchKey::BrlDot9 => Key::Key(Keyboard::BrlDot9),
+            SwitchKey::ButtonConfig => Key::Key(Keyboard::ButtonConfig),
+            SwitchKey::C => Key::Key(Keyboard::C),
+            SwitchKey::Calc => Key::Key(Keyboard::Calc),
+            SwitchKey::Calendar => Key::Key(Keyboard::Calendar),
+            SwitchKey::Camera => Key::Key(Keyboard::Camera),
+            SwitchKey::CameraDown => Key::Key(Keyboard::CameraDown),
+            SwitchKey::CameraFocus => Key::Key(Keyboard::CameraFocus),
+            SwitchKey::CameraLeft => Key::Key(Keyboard::CameraLeft),
+            SwitchKey::CameraRight => Key::Key(Keyboard::CameraRight),
+            SwitchKey::CameraUp => Key::Key(Keyboard::CameraUp),
+            SwitchKey::CameraZoomIn => Key::Key(Keyboard::CameraZoomIn),
+            SwitchKey::CameraZoomOut => Key::Key(Keyboard::CameraZoomOut),
+            SwitchKey::Cancel => Key::Key(Keyboard::Cancel),
+            SwitchKey::CapsLock => Key::Key(Keyboard::CapsLock),
+            SwitchKey::Cd => Key::Key(Keyboard::Cd),
+            SwitchKey::Channel => Key::Key(Keyboard::Channel),
+            SwitchKey::ChannelDown => Key::Key(Keyboard::ChannelDown),
+            SwitchKey::ChannelUp => Key::Key(Keyboard::ChannelUp),
+            SwitchKey::Chat => Key::Key(Keyboard::Chat),
+            SwitchKey::Clear => Key::Key(Keyboard::Clear),
+            SwitchKey::Close => Key::Key(Keyboard::Close),
+            SwitchKey::CloseCd => Key::Key(Keyboard::CloseCd),
+            SwitchKey::Coffee => Key::Key(Keyboard::Coffee),
+            SwitchKey::Comma => Key::Key(Keyboard::Comma),
+            SwitchKey::Compose => Key::Key(Keyboard::Compose),
+            SwitchKey::Computer => Key::Key(Keyboard::Computer),
+            SwitchKey::Config => Key::Key(Keyboard::Config),
+            SwitchKey::Connect => Key::Key(Keyboard::Connect),
+            SwitchKey::ContextMenu => Key::Key(Keyboard::ContextMenu),
+            SwitchKey::Controlpanel => Key::Key(Keyboard::Controlpanel),
+            SwitchKey::Copy => Key::Key(Keyboard::Copy),
+            SwitchKey::Cut => Key::Key(Keyboard::Cut),
+            SwitchKey::CycleWindows => Key::Key(Keyboard::CycleWindows),
+            SwitchKey::D => Key::Key(Keyboard::D),
+            SwitchKey::Dashboard => Key::Key(Keyboard::Dashboard),
+            SwitchKey::Data => Key::Key(Keyboard::Data),
+            SwitchKey::Database => Key::Key(Keyboard::Database),
+            SwitchKey::DelEol => Key::Key(Keyboard::DelEol),
+            SwitchKey::DelEos => Key::Key(Keyboard::DelEos),
+            SwitchKey::DelLine => Key::Key(Keyboard::DelLine),
+            SwitchKey::Delete => Key::Key(Keyboard::Delete),
+            SwitchKey::DeleteFile => Key::Key(Keyboard::DeleteFile),
+            SwitchKey::Digits => Key::Key(Keyboard::Digits),
+            SwitchKey::Directory => Key::Key(Keyboard::Directory),
+            SwitchKey::DisplayOff => Key::Key(Keyboard::DisplayOff),
+            SwitchKey::DisplayToggle => Key::Key(Keyboard::DisplayToggle),
+            SwitchKey::Documents => Key::Key(Keyboard::Documents),
+            SwitchKey::Dollar => Key::Key(Keyboard::Dollar),
+            SwitchKey::Dot => Key::Key(Keyboard::Dot),
+            SwitchKey::Down => Key::Key(Keyboard::Down),
+            SwitchKey::Dvd => Key::Key(Keyboard::Dvd),
+            SwitchKey::E => Key::Key(Keyboard::E),
+            SwitchKey::Edit => Key::Key(Keyboard::Edit),
+            SwitchKey::Editor => Key::Key(Keyboard::Editor),
+            SwitchKey::EjectCd => Key::Key(Keyboard::EjectCd),
+            SwitchKey::EjectCloseCd => Key::Key(Keyboard::EjectCloseCd),
+            SwitchKey::Email => Key::Key(Keyboard::Email),
+            SwitchKey::End => Key::Key(Keyboard::End),
+            SwitchKey::Enter => Key::Key(Keyboard::Enter),
+            SwitchKey::Epg => Key::Key(Keyboard::Epg),
+            SwitchKey::Equal => Key::Key(Keyboard::Equal),
+            SwitchKey::Esc => Key::Key(Keyboard::Esc),
+            SwitchKey::Euro => Key::Key(Keyboard::Euro),
+            SwitchKey::Exit => Key::Key(Keyboard::Exit),
+            SwitchKey::F => Key::Key(Keyboard::F),
+            SwitchKey::F1 => Key::Key(Keyboard::F1),
+            SwitchKey::F10 => Key::Key(Keyboard::F10),
+            SwitchKey::F11 => Key::Key(Keyboard::F11),
+            SwitchKey::F12 => Key::Key(Keyboard::F12),
+            SwitchKey::F13 => Key::Key(Keyboard::F13),
+            SwitchKey::F14 => Key::Key(Keyboard::F14),
+            SwitchKey::F15 => Key::Key(Keyboard::F15),
+            SwitchKey::F16 => Key::Key(Keyboard::F16),
+            SwitchKey::F17 => Key::Key(Keyboard::F17),
+            SwitchKey::F18 => Key::Key(Keyboard::F18),
+            SwitchKey::F19 => Key::Key(Keyboard::F19),
+            SwitchKey::F2 => Key::Key(Keyboard::F2),
+            SwitchKey::F20 => Key::Key(Keyboard::F20),
+            SwitchKey::F21 => Key::Key(Keyboard::F21),
+            SwitchKey::F22 => Key::Key(Keyboard::F22),
+            SwitchKey::F23 => Key::Key(Keyboard::F23),
+            SwitchKey::F24 => Key::Key(Keyboard::F24),
+            SwitchKey::F3 => Key::Key(Keyboard::F3),
+            SwitchKey::F4 => Key::Key(Keyboard::F4),
+            SwitchKey::F5 => Key::Key(Keyboard::F5),
+            SwitchKey::F6 => Key::Key(Keyboard::F6),
+            SwitchKey::F7 => Key::Key(Keyboard::F7),
+            SwitchKey::F8 => Key::Key(Keyboard::F8),
+            SwitchKey::F9 => Key::Key(Keyboard::F9),
+            SwitchKey::FastForward => Key::Key(Keyboard::FastForward),
+            SwitchKey::FastReverse => Key::Key(Keyboard::FastReverse),
+            SwitchKey::Favorites => Key::Key(Keyboard::Favorites),
+            SwitchKey::File => Key::Key(Keyboard::File),
+            SwitchKey::Finance => Key::Key(Keyboard::Finance),
+            SwitchKey::Find => Key::Key(Keyboard::Find),
+            SwitchKey::First => Key::Key(Keyboard::First),
+            SwitchKey::Fn => Key::Key(Keyboard::Fn),
+            SwitchKey::Fn1 => Key::Key(Keyboard::Fn1),
+            SwitchKey::Fn2 => Key::Key(Keyboard::Fn2),
+            SwitchKey::FnB => Key::Key(Keyboard::FnB),
+            SwitchKey::FnD => Key::Key(Keyboard::FnD),
+            SwitchKey::FnE => Key::Key(Keyboard::FnE),
+            SwitchKey::FnEsc => Key::Key(Keyboard::FnEsc),
+            SwitchKey::FnF => Key::Key(Keyboard::FnF),
+            SwitchKey::FnF1 => Key::Key(Keyboard::FnF1),
+            SwitchKey::FnF10 => Key::Key(Keyboard::FnF10),
+            SwitchKey::FnF11 => Key::Key(Keyboard::FnF11),
+            SwitchKey::FnF12 => Key::Key(Keyboard::FnF12),
+            SwitchKey::FnF2 => Key::Key(Keyboard::FnF2),
+            SwitchKey::FnF3 => Key::Key(Keyboard::FnF3),
+            SwitchKey::FnF4 => Key::Key(Keyboard::FnF4),
+            SwitchKey::FnF5 => Key::Key(Keyboard::FnF5),
+            SwitchKey::FnF6 => Key::Key(Keyboard::FnF6),
+            SwitchKey::FnF7 => Key::Key(Keyboard::FnF7),
+            SwitchKey::FnF8 => Key::Key(Keyboard::FnF8),
+            SwitchKey::FnF9 => Key::Key(Keyboard::FnF9),
+            SwitchKey::FnS => Key::Key(Keyboard::FnS),
+            SwitchKey::Forward => Key::Key(Keyboard::Forward),
+            SwitchKey::ForwardMail => Key::Key(Keyboard::ForwardMail),
+            SwitchKey::Frameback => Key::Key(Keyboard::Frameback),
+            SwitchKey::FrameForward => Key::Key(Keyboard::FrameForward),
+            SwitchKey::Front => Key::Key(Keyboard::Front),
+            SwitchKey::FullScreen => Key::Key(Keyboard::FullScreen),
+            SwitchKey::G => Key::Key(Keyboard::G),
+            SwitchKey::Games => Key::Key(Keyboard::Games),
+            SwitchKey::Goto => Key::Key(Keyboard::Goto),
+            SwitchKey::GraphicsEditor => Key::Key(Keyboard::GraphicsEditor),
+            SwitchKey::Grave => Key::Key(Keyboard::Grave),
+            SwitchKey::Green => Key::Key(Keyboard::Green),
+            SwitchKey::H => Key::Key(Keyboard::H),
+            SwitchKey::Hangeul => Key::Key(Keyboard::Hangeul),
+            SwitchKey::Hanja => Key::Key(Keyboard::Hanja),
+            SwitchKey::Help => Key::Key(Keyboard::Help),
+            SwitchKey::Henkan => Key::Key(Keyboard::Henkan),
+            SwitchKey::Hiragana => Key::Key(Keyboard::Hiragana),
+            SwitchKey::Home => Key::Key(Keyboard::Home),
+            SwitchKey::Homepage => Key::Key(Keyboard::Homepage),
+            SwitchKey::Hp => Key::Key(Keyboard::Hp),
+            SwitchKey::I => Key::Key(Keyboard::I),
+            SwitchKey::Images => Key::Key(Keyboard::Images),
+            SwitchKey::Info => Key::Key(Keyboard::Info),
+            SwitchKey::InsLine => Key::Key(Keyboard::InsLine),
+            SwitchKey::Insert => Key::Key(Keyboard::Insert),
+            SwitchKey::Iso => Key::Key(Keyboard::Iso),
+            SwitchKey::J => Key::Key(Keyboard::J),
+            SwitchKey::Journal => Key::Key(Keyboard::Journal),
+            SwitchKey::K => Key::Key(Keyboard::K),
+            SwitchKey::Katakana => Key::Key(Keyboard::Katakana),
+            SwitchKey::KatakanaHiragana => Key::Key(Keyboard::KatakanaHiragana),
+            SwitchKey::KbdLayoutNext => Key::Key(Keyboard::KbdLayoutNext),
+            SwitchKey::KbdLcdMenu1 => Key::Key(Keyboard::KbdLcdMenu1),
+            SwitchKey::KbdLcdMenu2 => Key::Key(Keyboard::KbdLcdMenu2),
+            SwitchKey::KbdLcdMenu3 => Key::Key(Keyboard::KbdLcdMenu3),
+            SwitchKey::KbdLcdMenu4 => Key::Key(Keyboard::KbdLcdMenu4),
+            SwitchKey::KbdLcdMenu5 => Key::Key(Keyboard::KbdLcdMenu5),
+            SwitchKey::KbdIllumDown => Key::Key(Keyboard::KbdIllumDown),
+            SwitchKey::KbdIllumToggle => Key::Key(Keyboard::KbdIllumToggle),
+            SwitchKey::KbdIllumUp => Key::Key(Keyboard::KbdIllumUp),
+            SwitchKey::KbdInputAssistAccept => Key::Key(Keyboard::KbdInputAssistAccept),
+            SwitchKey::KbdInputAssistCancel => Key::Key(Keyboard::KbdInputAssistCancel),
+            SwitchKey::KbdInputAssistNext => Key::Key(Keyboard::KbdInputAssistNext),
+            SwitchKey::KbdInputAssistNextgroup => Key::Key(Keyboard::KbdInputAssistNextgroup),
+            SwitchKey::KbdInputAssistPrev => Key::Key(Keyboard::KbdInputAssistPrev),
+            SwitchKey::KbdInputAssistPrevgroup => Key::Key(Keyboard::KbdInputAssistPrevgroup),
+            SwitchKey::Keyboard => Key::Key(Keyboard::Keyboard),
+            SwitchKey::Kp0 => Key::Key(Keyboard::Kp0),
+            SwitchKey::Kp1 => Key::Key(Keyboard::Kp1),
+            SwitchKey::Kp2 => Key::Key(Keyboard::Kp2),
+            SwitchKey::Kp3 => Key::Key(Keyboard::Kp3),
+            SwitchKey::Kp4 => Key::Key(Keyboard::Kp4),
+            SwitchKey::Kp5 => Key::Key(Keyboard::Kp5),
+            SwitchKey::Kp6 => Key::Key(Keyboard::Kp6),
+            SwitchKey::Kp7 => Key::Key(Keyboard::Kp7),
+            SwitchKey::Kp8 => Key::Key(Keyboard::Kp8),
+            SwitchKey::Kp9 => Key::Key(Keyboard::Kp9),
+            SwitchKey::KpAsterisk => Key::Key(Keyboard::KpAsterisk),
+            SwitchKey::KpComma => Key::Key(Keyboard::KpComma),
+            SwitchKey::KpDott => Key::Key(Keyboard::KpDot),
+            SwitchKey::KpEnter => Key::Key(Keyboard::KpEnter),
+            SwitchKey::KpEqual => Key::Key(Keyboard::KpEqual),
+            SwitchKey::KpJpComma => Key::Key(Keyboard::KpJpComma),
+            SwitchKey::KpLeftParen => Key::Key(Keyboard::KpLeftParen),
+            SwitchKey::KpMinus => Key::Key(Keyboard::KpMinus),
+            SwitchKey::KpPlus => Key::Key(Keyboard::KpPlus),
+            SwitchKey::KpPlusMinus => Key::Key(Keyboard::KpPlusMinus),
+            SwitchKey::KpRightParen => Key::Key(Keyboard::KpRightParen),
+            SwitchKey::KpSlash => Key::Key(Keyboard::KpSlash),
+            SwitchKey::L => Key::Key(Keyboard::L),
+            SwitchKey::Language => Key::Key(Keyboard::Language),
+            SwitchKey::Last => Key::Key(Keyboard::Last),
+            SwitchKey::Left => Key::Key(Keyboard::Left),
+            SwitchKey::LeftDown => Key::Key(Keyboard::LeftDown),
+            SwitchKey::LeftUp => Key::Key(Keyboard::LeftUp),
+            SwitchKey::LeftAlt => Key::Key(Keyboard::LeftAlt),
+            SwitchKey::LeftBrace => Key::Key(Keyboard::LeftBrace),
+            SwitchKey::LeftCtrl => Key::Key(Keyboard::LeftCtrl),
+            SwitchKey::LeftMeta => Key::Key(Keyboard::LeftMeta),
+            SwitchKey::LeftShift => Key::Key(Keyboard::LeftShift),
+            SwitchKey::LightsToggle => Key::Key(Keyboard::LightsToggle),
+            SwitchKey::LineFeed => Key::Key(Keyboard::LineFeed),
+            SwitchKey::List => Key::Key(Keyboard::List),
+            SwitchKey::LogOff => Key::Key(Keyboard::LogOff),
+            SwitchKey::M => Key::Key(Keyboard::M),
+            SwitchKey::Macro => Key::Key(Keyboard::Macro),
+            SwitchKey::Macro1 => Key::Key(Keyboard::Macro1),
+            SwitchKey::Macro10 => Key::Key(Keyboard::Macro10),
+            SwitchKey::Macro11 => Key::Key(Keyboard::Macro11),
+            SwitchKey::Macro12 => Key::Key(Keyboard::Macro12),
+            SwitchKey::Macro13 => Key::Key(Keyboard::Macro13),
+            SwitchKey::Macro14 => Key::Key(Keyboard::Macro14),
+            SwitchKey::Macro15 => Key::Key(Keyboard::Macro15),
+            SwitchKey::Macro16 => Key::Key(Keyboard::Macro16),
+            SwitchKey::Macro17 => Key::Key(Keyboard::Macro17),
+            SwitchKey::Macro18 => Key::Key(Keyboard::Macro18),
+            SwitchKey::Macro19 => Key::Key(Keyboard::Macro19),
+            SwitchKey::Macro2 => Key::Key(Keyboard::Macro2),
+            SwitchKey::Macro20 => Key::Key(Keyboard::Macro20),
+            SwitchKey::Macro21 => Key::Key(Keyboard::Macro21),
+            SwitchKey::Macro22 => Key::Key(Keyboard::Macro22),
+            SwitchKey::Macro23 => Key::Key(Keyboard::Macro23),
+            SwitchKey::Macro24 => Key::Key(Keyboard::Macro24),
+            SwitchKey::Macro25 => Key::Key(Keyboard::Macro25),
+            SwitchKey::Macro26 => Key::Key(Keyboard::Macro26),
+            SwitchKey::Macro27 => Key::Key(Keyboard::Macro27),
+            SwitchKey::Macro28 => Key::Key(Keyboard::Macro28),
+            SwitchKey::Macro29 => Key::Key(Keyboard::Macro29),
+            SwitchKey::Macro3 => Key::Key(Keyboard::Macro3),
+            SwitchKey::Macro30 => Key::Key(Keyboard::Macro30),
+            SwitchKey::Macro4 => Key::Key(Keyboard::Macro4),
+            SwitchKey::Macro5 => Key::Key(Keyboard::Macro5),
+            SwitchKey::Macro6 => Key::Key(Keyboard::Macro6),
+            SwitchKey::Macro7 => Key::Key(Keyboard::Macro7),
+            SwitchKey::Macro8 => Key::Key(Keyboard::Macro8),
+            SwitchKey::Macro9 => Key::Key(Keyboard::Macro9),
+            SwitchKey::MacroPreset1 => Key::Key(Keyboard::MacroPreset1),
+            SwitchKey::MacroPreset2 => Key::Key(Keyboard::MacroPreset2),
+            SwitchKey::MacroPreset3 => Key::Key(Keyboard::MacroPreset3),
+            SwitchKey::MacroPresetCycle => Key::Key(Keyboard::MacroPresetCycle),
+            SwitchKey::MacroRecordStart => Key::Key(Keyboard::MacroRecordStart),
+            SwitchKey::MacroRecordStop => Key::Key(Keyboard::MacroRecordStop),
+            SwitchKey::Mail => Key::Key(Keyboard::Mail),
+            SwitchKey::Media => Key::Key(Keyboard::Media),
+            SwitchKey::MediaRepeat => Key::Key(Keyboard::MediaRepeat),
+            SwitchKey::MediaTopMenu => Key::Key(Keyboard::MediaTopMenu),
+            SwitchKey::Memo => Key::Key(Keyboard::Memo),
+            SwitchKey::Menu => Key::Key(Keyboard::Menu),
+            SwitchKey::Messenger => Key::Key(Keyboard::Messenger),
+            SwitchKey::Mhp => Key::Key(Keyboard::Mhp),
+            SwitchKey::MicMute => Key::Key(Keyboard::MicMute),
+            SwitchKey::Minus => Key::Key(Keyboard::Minus),
+            SwitchKey::Mode => Key::Key(Keyboard::Mode),
+            SwitchKey::Move => Key::Key(Keyboard::Move),
+            SwitchKey::Mp3 => Key::Key(Keyboard::Mp3),
+            SwitchKey::MsDos => Key::Key(Keyboard::MsDos),
+            SwitchKey::Muhenkan => Key::Key(Keyboard::Muhenkan),
+            SwitchKey::Mute => Key::Key(Keyboard::Mute),
+            SwitchKey::N => Key::Key(Keyboard::N),
+            SwitchKey::N0 => Key::Key(Keyboard::N0),
+            SwitchKey::N1 => Key::Key(Keyboard::N1),
+            SwitchKey::N102nd => Key::Key(Keyboard::N102nd),
+            SwitchKey::N10ChannelsDown => Key::Key(Keyboard::N10ChannelsDown),
+            SwitchKey::N10ChannelsUp => Key::Key(Keyboard::N10ChannelsUp),
+            SwitchKey::N2 => Key::Key(Keyboard::N2),
+            SwitchKey::N3 => Key::Key(Keyboard::N3),
+            SwitchKey::N3dMode => Key::Key(Keyboard::N3dMode),
+            SwitchKey::N4 => Key::Key(Keyboard::N4),
+            SwitchKey::N5 => Key::Key(Keyboard::N5),
+            SwitchKey::N6 => Key::Key(Keyboard::N6),
+            SwitchKey::N7 => Key::Key(Keyboard::N7),
+            SwitchKey::N8 => Key::Key(Keyboard::N8),
+            SwitchKey::N9 => Key::Key(Keyboard::N9),
+            SwitchKey::New => Key::Key(Keyboard::New),
+            SwitchKey::News => Key::Key(Keyboard::News),
+            SwitchKey::Next => Key::Key(Keyboard::Next),
+            SwitchKey::NextFavorite => Key::Key(Keyboard::NextFavorite),
+            SwitchKey::NextSong => Key::Key(Keyboard::NextSong),
+            SwitchKey::Numeric0 => Key::Key(Keyboard::Numeric0),
+            SwitchKey::Numeric1 => Key::Key(Keyboard::Numeric1),
+            SwitchKey::Numeric11 => Key::Key(Keyboard::Numeric11),
+            SwitchKey::Numeric12 => Key::Key(Keyboard::Numeric12),
+            SwitchKey::Numeric2 => Key::Key(Keyboard::Numeric2),
+            SwitchKey::Numeric3 => Key::Key(Keyboard::Numeric3),
+            SwitchKey::Numeric4 => Key::Key(Keyboard::Numeric4),
+            SwitchKey::Numeric5 => Key::Key(Keyboard::Numeric5),
+            SwitchKey::Numeric6 => Key::Key(Keyboard::Numeric6),
+            SwitchKey::Numeric7 => Key::Key(Keyboard::Numeric7),
+            SwitchKey::Numeric8 => Key::Key(Keyboard::Numeric8),
+            SwitchKey::Numeric9 => Key::Key(Keyboard::Numeric9),
+            SwitchKey::NumericA => Key::Key(Keyboard::NumericA),
+            SwitchKey::NumericB => Key::Key(Keyboard::NumericB),
+            SwitchKey::NumericC => Key::Key(Keyboard::NumericC),
+            SwitchKey::NumericD => Key::Key(Keyboard::NumericD),
+            SwitchKey::NumericPound => Key::Key(Keyboard::NumericPound),
+            SwitchKey::NumericStar => Key::Key(Keyboard::NumericStar),
+            SwitchKey::NumLock => Key::Key(Keyboard::NumLock),
+            SwitchKey::O => Key::Key(Keyboard::O),
+            SwitchKey::Ok => Key::Key(Keyboard::Ok),
+            SwitchKey::OnscreenKeyboard => Key::Key(Keyboard::OnscreenKeyboard),
+            SwitchKey::Open => Key::Key(Keyboard::Open),
+            SwitchKey::Option => Key::Key(Keyboard::Option),
+            SwitchKey::P => Key::Key(Keyboard::P),
+            SwitchKey::PageDown => Key::Key(Keyboard::PageDown),
+            SwitchKey::PageUp => Key::Key(Keyboard::PageUp),
+            SwitchKey::Paste => Key::Key(Keyboard::Paste),
+            SwitchKey::Pause => Key::Key(Keyboard::Pause),
+            SwitchKey::PauseRecord => Key::Key(Keyboard::PauseRecord),
+            SwitchKey::PauseCd => Key::Key(Keyboard::PauseCd),
+            SwitchKey::Pc => Key::Key(Keyboard::Pc),
+            SwitchKey::Phone => Key::Key(Keyboard::Phone),
+            SwitchKey::Play => Key::Key(Keyboard::Play),
+            SwitchKey::PlayCd => Key::Key(Keyboard::PlayCd),
+            SwitchKey::Player => Key::Key(Keyboard::Player),
+            SwitchKey::PlayPause => Key::Key(Keyboard::PlayPause),
+            SwitchKey::Power => Key::Key(Keyboard::Power),
+            SwitchKey::Power2 => Key::Key(Keyboard::Power2),
+            SwitchKey::Presentation => Key::Key(Keyboard::Presentation),
+            SwitchKey::Previous => Key::Key(Keyboard::Previous),
+            SwitchKey::PreviousSong => Key::Key(Keyboard::PreviousSong),
+            SwitchKey::Print => Key::Key(Keyboard::Print),
+            SwitchKey::PrivacyScreenToggle => Key::Key(Keyboard::PrivacyScreenToggle),
+            SwitchKey::Prog1 => Key::Key(Keyboard::Prog1),
+            SwitchKey::Prog2 => Key::Key(Keyboard::Prog2),
+            SwitchKey::Prog3 => Key::Key(Keyboard::Prog3),
+            SwitchKey::Prog4 => Key::Key(Keyboard::Prog4),
+            SwitchKey::Program => Key::Key(Keyboard::Program),
+            SwitchKey::Props => Key::Key(Keyboard::Props),
+            SwitchKey::Pvr => Key::Key(Keyboard::Pvr),
+            SwitchKey::Q => Key::Key(Keyboard::Q),
+            SwitchKey::Question => Key::Key(Keyboard::Question),
+            SwitchKey::R => Key::Key(Keyboard::R),
+            SwitchKey::Radio => Key::Key(Keyboard::Radio),
+            SwitchKey::Record => Key::Key(Keyboard::Record),
+            SwitchKey::Red => Key::Key(Keyboard::Red),
+            SwitchKey::Redo => Key::Key(Keyboard::Redo),
+            SwitchKey::Refresh => Key::Key(Keyboard::Refresh),
+            SwitchKey::Reply => Key::Key(Keyboard::Reply),
+            SwitchKey::Reserved => Key::Key(Keyboard::Reserved),
+            SwitchKey::Restart => Key::Key(Keyboard::Restart),
+            SwitchKey::Rewind => Key::Key(Keyboard::Rewind),
+            SwitchKey::RfKill => Key::Key(Keyboard::RfKill),
+            SwitchKey::Right => Key::Key(Keyboard::Right),
+            SwitchKey::RightDown => Key::Key(Keyboard::RightDown),
+            SwitchKey::RightUp => Key::Key(Keyboard::RightUp),
+            SwitchKey::RightAlt => Key::Key(Keyboard::RightAlt),
+            SwitchKey::RightBrace => Key::Key(Keyboard::RightBrace),
+            SwitchKey::RightCtrl => Key::Key(Keyboard::RightCtrl),
+            SwitchKey::RightMeta => Key::Key(Keyboard::RightMeta),
+            SwitchKey::RightShift => Key::Key(Keyboard::RightShift),
+            SwitchKey::Ro => Key::Key(Keyboard::Ro),
+            SwitchKey::RootMenu => Key::Key(Keyboard::RootMenu),
+            SwitchKey::RotateDisplay => Key::Key(Keyboard::RotateDisplay),
+            SwitchKey::RotateLockToggle => Key::Key(Keyboard::RotateLockToggle),
+            SwitchKey::S => Key::Key(Keyboard::S),
+            SwitchKey::Sat => Key::Key(Keyboard::Sat),
+            SwitchKey::Sat2 => Key::Key(Keyboard::Sat2),
+            SwitchKey::Save => Key::Key(Keyboard::Save),
+            SwitchKey::Scale => Key::Key(Keyboard::Scale),
+            SwitchKey::Screensaver => Key::Key(Keyboard::Screensaver),
+            SwitchKey::ScrollDown => Key::Key(Keyboard::ScrollDown),
+            SwitchKey::ScrollLock => Key::Key(Keyboard::ScrollLock),
+            SwitchKey::ScrollUp => Key::Key(Keyboard::ScrollUp),
+            SwitchKey::Search => Key::Key(Keyboard::Search),
+            SwitchKey::Select => Key::Key(Keyboard::Select),
+            SwitchKey::SelectiveScreenshot => Key::Key(Keyboard::SelectiveScreenshot),
+            SwitchKey::Semicolon => Key::Key(Keyboard::Semicolon),
+            SwitchKey::Send => Key::Key(Keyboard::Send),
+            SwitchKey::SendFile => Key::Key(Keyboard::SendFile),
+            SwitchKey::Setup => Key::Key(Keyboard::Setup),
+            SwitchKey::Shop => Key::Key(Keyboard::Shop),
+            SwitchKey::Shuffle => Key::Key(Keyboard::Shuffle),
+            SwitchKey::Slash => Key::Key(Keyboard::Slash),
+            SwitchKey::Sleep => Key::Key(Keyboard::Sleep),
+            SwitchKey::Slow => Key::Key(Keyboard::Slow),
+            SwitchKey::SlowReverse => Key::Key(Keyboard::SlowReverse),
+            SwitchKey::Sound => Key::Key(Keyboard::Sound),
+            SwitchKey::Space => Key::Key(Keyboard::Space),
+            SwitchKey::Spellcheck => Key::Key(Keyboard::Spellcheck),
+            SwitchKey::Sport => Key::Key(Keyboard::Sport),
+            SwitchKey::Spreadsheet => Key::Key(Keyboard::Spreadsheet),
+            SwitchKey::Stop => Key::Key(Keyboard::Stop),
+            SwitchKey::StopRecord => Key::Key(Keyboard::StopRecord),
+            SwitchKey::StopCd => Key::Key(Keyboard::StopCd),
+            SwitchKey::Subtitle => Key::Key(Keyboard::Subtitle),
+            SwitchKey::Suspend => Key::Key(Keyboard::Suspend),
+            SwitchKey::SwitchVideoMode => Key::Key(Keyboard::SwitchVideoMode),
+            SwitchKey::SysRq => Key::Key(Keyboard::SysRq),
+            SwitchKey::T => Key::Key(Keyboard::T),
+            SwitchKey::Tab => Key::Key(Keyboard::Tab),
+            SwitchKey::Tape => Key::Key(Keyboard::Tape),
+            SwitchKey::TaskManager => Key::Key(Keyboard::TaskManager),
+            SwitchKey::Teen => Key::Key(Keyboard::Teen),
+            SwitchKey::Text => Key::Key(Keyboard::Text),
+            SwitchKey::Time => Key::Key(Keyboard::Time),
+            SwitchKey::Title => Key::Key(Keyboard::Title),
+            SwitchKey::TouchpadOff => Key::Key(Keyboard::TouchpadOff),
+            SwitchKey::TouchpadOn => Key::Key(Keyboard::TouchpadOn),
+            SwitchKey::TouchpadToggle => Key::Key(Keyboard::TouchpadToggle),
+            SwitchKey::Tuner => Key::Key(Keyboard::Tuner),
+            SwitchKey::Tv => Key::Key(Keyboard::Tv),
+            SwitchKey::Tv2 => Key::Key(Keyboard::Tv2),
+            SwitchKey::Twen => Key::Key(Keyboard::Twen),
+            SwitchKey::U => Key::Key(Keyboard::U),
+            SwitchKey::Undo => Key::Key(Keyboard::Undo),
+            SwitchKey::Unknown => Key::Key(Keyboard::Unknown),
+            SwitchKey::Unmute => Key::Key(Keyboard::Unmute),
+            SwitchKey::Up => Key::Key(Keyboard::Up),
+            SwitchKey::Uwb => Key::Key(Keyboard::Uwb),
+            SwitchKey::V => Key::Key(Keyboard::V),
+            SwitchKey::Vcr => Key::Key(Keyboard::Vcr),
+            SwitchKey::Vcr2 => Key::Key(Keyboard::Vcr2),
+            SwitchKey::Vendor => Key::Key(Keyboard::Vendor),
+            SwitchKey::Video => Key::Key(Keyboard::Video),
+            SwitchKey::VideoNext => Key::Key(Keyboard::VideoNext),
+            SwitchKey::VideoPrev => Key::Key(Keyboard::VideoPrev),
+            SwitchKey::VideoPhone => Key::Key(Keyboard::VideoPhone),
+            SwitchKey::Vod => Key::Key(Keyboard::Vod),
+            SwitchKey::VoiceCommand => Key::Key(Keyboard::VoiceCommand),
+            SwitchKey::VoiceMail => Key::Key(Keyboard::VoiceMail),
+            SwitchKey::VolumeDown => Key::Key(Keyboard::VolumeDown),
+            SwitchKey::VolumeUp => Key::Key(Keyboard::VolumeUp),
+            SwitchKey::W => Key::Key(Keyboard::W),
+            SwitchKey::WakeUp => Key::Key(Keyboard::WakeUp),
+            SwitchKey::Wlan => Key::Key(Keyboard::Wlan),
+            SwitchKey::WordProcessor => Key::Key(Keyboard::WordProcessor),
+            SwitchKey::WpsButton => Key::Key(Keyboard::WpsButton),
+            SwitchKey::Wwan => Key::Key(Keyboard::Wwan),
+            SwitchKey::Www => Key::Key(Keyboard::Www),
+            SwitchKey::X => Key::Key(Keyboard::X),
+            SwitchKey::Xfer => Key::Key(Keyboard::Xfer),
+            SwitchKey::Y => Key::Key(Keyboard::Y),
+            SwitchKey::Yellow => Key::Key(Keyboard::Yellow),
+            SwitchKey::Yen => Key::Key(Keyboard::Yen),
+            SwitchKey::Z => Key::Key(Keyboard::Z),
+            SwitchKey::ZenkakuHankaku => Key::Key(Keyboard::ZenkakuHankaku),
+            SwitchKey::ZoomIn => Key::Key(Keyboard::ZoomIn),
+            SwitchKey::ZoomOut => Key::Key(Keyboard::ZoomOut),
+            SwitchKey::ZoomReset => Key::Key(Keyboard::ZoomReset),
 
             // Buttons.
-            Self::B0 => Key::Button(Button::B0),
-            Self::B1 => Key::Button(Button::B1),
-            Self::B2 => Key::Button(Button::B2),
-            Self::B3 => Key::Button(Button::B3),
-            Self::B4 => Key::Button(Button::B4),
-            Self::B5 => Key::Button(Button::B5),
-            Self::B6 => Key::Button(Button::B6),
-            Self::B7 => Key::Button(Button::B7),
-            Self::B8 => Key::Button(Button::B8),
-            Self::B9 => Key::Button(Button::B9),
-            Self::BLeft => Key::Button(Button::Left),
-            Self::BRight => Key::Button(Button::Right),
-            Self::Middle => Key::Button(Button::Middle),
-            Self::Side => Key::Button(Button::Side),
-            Self::Extra => Key::Button(Button::Extra),
-            Self::BForward => Key::Button(Button::Forward),
-            Self::BBack => Key::Button(Button::Back),
-            Self::Task => Key::Button(Button::Task),
-            Self::Trigger => Key::Button(Button::Trigger),
-            Self::Thumb => Key::Button(Button::Thumb),
-            Self::Thumb2 => Key::Button(Button::Thumb2),
-            Self::Top => Key::Button(Button::Top),
-            Self::Top2 => Key::Button(Button::Top2),
-            Self::Pinkie => Key::Button(Button::Pinkie),
-            Self::Base => Key::Button(Button::Base),
-            Self::Base2 => Key::Button(Button::Base2),
-            Self::Base3 => Key::Button(Button::Base3),
-            Self::Base4 => Key::Button(Button::Base4),
-            Self::Base5 => Key::Button(Button::Base5),
-            Self::Base6 => Key::Button(Button::Base6),
-            Self::Dead => Key::Button(Button::Dead),
-            Self::South => Key::Button(Button::South),
-            Self::East => Key::Button(Button::East),
-            Self::BC => Key::Button(Button::C),
-            Self::North => Key::Button(Button::North),
-            Self::West => Key::Button(Button::West),
-            Self::BZ => Key::Button(Button::Z),
-            Self::TL => Key::Button(Button::TL),
-            Self::Tr => Key::Button(Button::Tr),
-            Self::Tl2 => Key::Button(Button::Tl2),
-            Self::Tr2 => Key::Button(Button::Tr2),
-            Self::BSelect => Key::Button(Button::Select),
-            Self::Start => Key::Button(Button::Start),
-            Self::BMode => Key::Button(Button::Mode),
-            Self::ThumbL => Key::Button(Button::ThumbL),
-            Self::ThumbR => Key::Button(Button::ThumbR),
-            Self::ToolPen => Key::Button(Button::ToolPen),
-            Self::ToolRubber => Key::Button(Button::ToolRubber),
-            Self::ToolBrush => Key::Button(Button::ToolBrush),
-            Self::ToolPencil => Key::Button(Button::ToolPencil),
-            Self::ToolAirbrush => Key::Button(Button::ToolAirbrush),
-            Self::ToolFinger => Key::Button(Button::ToolFinger),
-            Self::ToolMouse => Key::Button(Button::ToolMouse),
-            Self::ToolLens => Key::Button(Button::ToolLens),
-            Self::QuintTap => Key::Button(Button::QuintTap),
-            Self::Stylus3 => Key::Button(Button::Stylus3),
-            Self::Touch => Key::Button(Button::Touch),
-            Self::Stylus => Key::Button(Button::Stylus),
-            Self::Stylus2 => Key::Button(Button::Stylus2),
-            Self::DoubleTap => Key::Button(Button::DoubleTap),
-            Self::TripleTap => Key::Button(Button::TripleTap),
-            Self::QuadTap => Key::Button(Button::QuadTap),
-            Self::GearDown => Key::Button(Button::GearDown),
-            Self::GearUp => Key::Button(Button::GearUp),
-            Self::DPadUp => Key::Button(Button::DPadUp),
-            Self::DPadDown => Key::Button(Button::DPadDown),
-            Self::DPadLeft => Key::Button(Button::DPadLeft),
-            Self::DPadRight => Key::Button(Button::DPadRight),
-            Self::TriggerHappy1 => Key::Button(Button::TriggerHappy1),
-            Self::TrigerHappy2 => Key::Button(Button::TrigerHappy2),
-            Self::TriggerHappy3 => Key::Button(Button::TriggerHappy3),
-            Self::TriggerHappy4 => Key::Button(Button::TriggerHappy4),
-            Self::TriggerHappy5 => Key::Button(Button::TriggerHappy5),
-            Self::TriggerHappy6 => Key::Button(Button::TriggerHappy6),
-            Self::TriggerHappy7 => Key::Button(Button::TriggerHappy7),
-            Self::TriggerHappy8 => Key::Button(Button::TriggerHappy8),
-            Self::TriggerHappy9 => Key::Button(Button::TriggerHappy9),
-            Self::TriggerHappy10 => Key::Button(Button::TriggerHappy10),
-            Self::TriggerHappy11 => Key::Button(Button::TriggerHappy11),
-            Self::TriggerHappy12 => Key::Button(Button::TriggerHappy12),
-            Self::TriggerHappy13 => Key::Button(Button::TriggerHappy13),
-            Self::TriggerHappy14 => Key::Button(Button::TriggerHappy14),
-            Self::TriggerHappy15 => Key::Button(Button::TriggerHappy15),
-            Self::TriggerHappy16 => Key::Button(Button::TriggerHappy16),
-            Self::TriggerHappy17 => Key::Button(Button::TriggerHappy17),
-            Self::TriggerHappy18 => Key::Button(Button::TriggerHappy18),
-            Self::TriggerHappy19 => Key::Button(Button::TriggerHappy19),
-            Self::TriggerHappy20 => Key::Button(Button::TriggerHappy20),
-            Self::TriggerHappy21 => Key::Button(Button::TriggerHappy21),
-            Self::TriggerHappy22 => Key::Button(Button::TriggerHappy22),
-            Self::TriggerHappy23 => Key::Button(Button::TriggerHappy23),
-            Self::TriggerHappy24 => Key::Button(Button::TriggerHappy24),
-            Self::TriggerHappy25 => Key::Button(Button::TriggerHappy25),
-            Self::TriggerHappy26 => Key::Button(Button::TriggerHappy26),
-            Self::TriggerHappy27 => Key::Button(Button::TriggerHappy27),
-            Self::TriggerHappy28 => Key::Button(Button::TriggerHappy28),
-            Self::TriggerHappy29 => Key::Button(Button::TriggerHappy29),
-            Self::TriggerHappy30 => Key::Button(Button::TriggerHappy30),
-            Self::TriggerHappy31 => Key::Button(Button::TriggerHappy31),
-            Self::TriggerHappy32 => Key::Button(Button::TriggerHappy32),
-            Self::TriggerHappy33 => Key::Button(Button::TriggerHappy33),
-            Self::TriggerHappy34 => Key::Button(Button::TriggerHappy34),
-            Self::TriggerHappy35 => Key::Button(Button::TriggerHappy35),
-            Self::TriggerHappy36 => Key::Button(Button::TriggerHappy36),
-            Self::TriggerHappy37 => Key::Button(Button::TriggerHappy37),
-            Self::TriggerHappy38 => Key::Button(Button::TriggerHappy38),
-            Self::TriggerHappy39 => Key::Button(Button::TriggerHappy39),
-            Self::TriggerHappy40 => Key::Button(Button::TriggerHappy40),
+            SwitchKey::B0 => Key::Button(Button::B0),
+            SwitchKey::B1 => Key::Button(Button::B1),
+            SwitchKey::B2 => Key::Button(Button::B2),
+            SwitchKey::B3 => Key::Button(Button::B3),
+            SwitchKey::B4 => Key::Button(Button::B4),
+            SwitchKey::B5 => Key::Button(Button::B5),
+            SwitchKey::B6 => Key::Button(Button::B6),
+            SwitchKey::B7 => Key::Button(Button::B7),
+            SwitchKey::B8 => Key::Button(Button::B8),
+            SwitchKey::B9 => Key::Button(Button::B9),
+            SwitchKey::BLeft => Key::Button(Button::Left),
+            SwitchKey::BRight => Key::Button(Button::Right),
+            SwitchKey::Middle => Key::Button(Button::Middle),
+            SwitchKey::Side => Key::Button(Button::Side),
+            SwitchKey::Extra => Key::Button(Button::Extra),
+            SwitchKey::BForward => Key::Button(Button::Forward),
+            SwitchKey::BBack => Key::Button(Button::Back),
+            SwitchKey::Task => Key::Button(Button::Task),
+            SwitchKey::Trigger => Key::Button(Button::Trigger),
+            SwitchKey::Thumb => Key::Button(Button::Thumb),
+            SwitchKey::Thumb2 => Key::Button(Button::Thumb2),
+            SwitchKey::Top => Key::Button(Button::Top),
+            SwitchKey::Top2 => Key::Button(Button::Top2),
+            SwitchKey::Pinkie => Key::Button(Button::Pinkie),
+            SwitchKey::Base => Key::Button(Button::Base),
+            SwitchKey::Base2 => Key::Button(Button::Base2),
+            SwitchKey::Base3 => Key::Button(Button::Base3),
+            SwitchKey::Base4 => Key::Button(Button::Base4),
+            SwitchKey::Base5 => Key::Button(Button::Base5),
+            SwitchKey::Base6 => Key::Button(Button::Base6),
+            SwitchKey::Dead => Key::Button(Button::Dead),
+            SwitchKey::South => Key::Button(Button::South),
+            SwitchKey::East => Key::Button(Button::East),
+            SwitchKey::BC => Key::Button(Button::C),
+            SwitchKey::North => Key::Button(Button::North),
+            SwitchKey::West => Key::Button(Button::West),
+            SwitchKey::BZ => Key::Button(Button::Z),
+            SwitchKey::TL => Key::Button(Button::TL),
+            SwitchKey::Tr => Key::Button(Button::Tr),
+            SwitchKey::Tl2 => Key::Button(Button::Tl2),
+            SwitchKey::Tr2 => Key::Button(Button::Tr2),
+            SwitchKey::BSelect => Key::Button(Button::Select),
+            SwitchKey::Start => Key::Button(Button::Start),
+            SwitchKey::BMode => Key::Button(Button::Mode),
+            SwitchKey::ThumbL => Key::Button(Button::ThumbL),
+            SwitchKey::ThumbR => Key::Button(Button::ThumbR),
+            SwitchKey::ToolPen => Key::Button(Button::ToolPen),
+            SwitchKey::ToolRubber => Key::Button(Button::ToolRubber),
+            SwitchKey::ToolBrush => Key::Button(Button::ToolBrush),
+            SwitchKey::ToolPencil => Key::Button(Button::ToolPencil),
+            SwitchKey::ToolAirbrush => Key::Button(Button::ToolAirbrush),
+            SwitchKey::ToolFinger => Key::Button(Button::ToolFinger),
+            SwitchKey::ToolMouse => Key::Button(Button::ToolMouse),
+            SwitchKey::ToolLens => Key::Button(Button::ToolLens),
+            SwitchKey::QuintTap => Key::Button(Button::QuintTap),
+            SwitchKey::Stylus3 => Key::Button(Button::Stylus3),
+            SwitchKey::Touch => Key::Button(Button::Touch),
+            SwitchKey::Stylus => Key::Button(Button::Stylus),
+            SwitchKey::Stylus2 => Key::Button(Button::Stylus2),
+            SwitchKey::DoubleTap => Key::Button(Button::DoubleTap),
+            SwitchKey::TripleTap => Key::Button(Button::TripleTap),
+            SwitchKey::QuadTap => Key::Button(Button::QuadTap),
+            SwitchKey::GearDown => Key::Button(Button::GearDown),
+            SwitchKey::GearUp => Key::Button(Button::GearUp),
+            SwitchKey::DPadUp => Key::Button(Button::DPadUp),
+            SwitchKey::DPadDown => Key::Button(Button::DPadDown),
+            SwitchKey::DPadLeft => Key::Button(Button::DPadLeft),
+            SwitchKey::DPadRight => Key::Button(Button::DPadRight),
+            SwitchKey::TriggerHappy1 => Key::Button(Button::TriggerHappy1),
+            SwitchKey::TrigerHappy2 => Key::Button(Button::TrigerHappy2),
+            SwitchKey::TriggerHappy3 => Key::Button(Button::TriggerHappy3),
+            SwitchKey::TriggerHappy4 => Key::Button(Button::TriggerHappy4),
+            SwitchKey::TriggerHappy5 => Key::Button(Button::TriggerHappy5),
+            SwitchKey::TriggerHappy6 => Key::Button(Button::TriggerHappy6),
+            SwitchKey::TriggerHappy7 => Key::Button(Button::TriggerHappy7),
+            SwitchKey::TriggerHappy8 => Key::Button(Button::TriggerHappy8),
+            SwitchKey::TriggerHappy9 => Key::Button(Button::TriggerHappy9),
+            SwitchKey::TriggerHappy10 => Key::Button(Button::TriggerHappy10),
+            SwitchKey::TriggerHappy11 => Key::Button(Button::TriggerHappy11),
+            SwitchKey::TriggerHappy12 => Key::Button(Button::TriggerHappy12),
+            SwitchKey::TriggerHappy13 => Key::Button(Button::TriggerHappy13),
+            SwitchKey::TriggerHappy14 => Key::Button(Button::TriggerHappy14),
+            SwitchKey::TriggerHappy15 => Key::Button(Button::TriggerHappy15),
+            SwitchKey::TriggerHappy16 => Key::Button(Button::TriggerHappy16),
+            SwitchKey::TriggerHappy17 => Key::Button(Button::TriggerHappy17),
+            SwitchKey::TriggerHappy18 => Key::Button(Button::TriggerHappy18),
+            SwitchKey::TriggerHappy19 => Key::Button(Button::TriggerHappy19),
+            SwitchKey::TriggerHappy20 => Key::Button(Button::TriggerHappy20),
+            SwitchKey::TriggerHappy21 => Key::Button(Button::TriggerHappy21),
+            SwitchKey::TriggerHappy22 => Key::Button(Button::TriggerHappy22),
+            SwitchKey::TriggerHappy23 => Key::Button(Button::TriggerHappy23),
+            SwitchKey::TriggerHappy24 => Key::Button(Button::TriggerHappy24),
+            SwitchKey::TriggerHappy25 => Key::Button(Button::TriggerHappy25),
+            SwitchKey::TriggerHappy26 => Key::Button(Button::TriggerHappy26),
+            SwitchKey::TriggerHappy27 => Key::Button(Button::TriggerHappy27),
+            SwitchKey::TriggerHappy28 => Key::Button(Button::TriggerHappy28),
+            SwitchKey::TriggerHappy29 => Key::Button(Button::TriggerHappy29),
+            SwitchKey::TriggerHappy30 => Key::Button(Button::TriggerHappy30),
+            SwitchKey::TriggerHappy31 => Key::Button(Button::TriggerHappy31),
+            SwitchKey::TriggerHappy32 => Key::Button(Button::TriggerHappy32),
+            SwitchKey::TriggerHappy33 => Key::Button(Button::TriggerHappy33),
+            SwitchKey::TriggerHappy34 => Key::Button(Button::TriggerHappy34),
+            SwitchKey::TriggerHappy35 => Key::Button(Button::TriggerHappy35),
+            SwitchKey::TriggerHappy36 => Key::Button(Button::TriggerHappy36),
+            SwitchKey::TriggerHappy37 => Key::Button(Button::TriggerHappy37),
+            SwitchKey::TriggerHappy38 => Key::Button(Button::TriggerHappy38),
+            SwitchKey::TriggerHappy39 => Key::Button(Button::TriggerHappy39),
+            SwitchKey::TriggerHappy40 => Key::Button(Button::TriggerHappy40),
+            // Checked when parsing.
+            SwitchKey::Raw(code) => Key::from_code(code).unwrap(),
         }
     }
 }
@@ -1243,6 +1476,8 @@ impl Into<Key> for SwitchKey {
 #[cfg(test)]
 mod test {
     use super::*;
+    use rkvm_input::device::{Bus, DeviceClass, DeviceInfo};
+    use std::ffi::CStr;
 
     #[test]
     fn example_parses() {
@@ -1255,4 +1490,145 @@ mod test {
         let config = include_str!("../../example/server-with-allowlist.toml");
         toml::from_str::<Config>(config).unwrap();
     }
+
+    #[test]
+    fn key_codes_parse() {
+        let config =
+            toml::from_str::<BroadcastGroupConfig>(r#"keys = ["left-alt", 30, 249]"#).unwrap();
+        let keys = config
+            .keys
+            .into_iter()
+            .map(Into::into)
+            .collect::<HashSet<Key>>();
+
+        // Codes of keys with a name are the same as the name.
+        assert_eq!(
+            keys,
+            [
+                Key::Key(Keyboard::LeftAlt),
+                Key::Key(Keyboard::A),
+                Key::Raw(249)
+            ]
+            .into()
+        );
+
+        let client = toml::from_str::<ClientConfig>(r#"remap = { 249 = "a" }"#).unwrap();
+        assert!(client.remap[&SwitchKey::Raw(249)] == SwitchKey::A);
+
+        assert!(toml::from_str::<BroadcastGroupConfig>(r#"keys = [768]"#).is_err());
+        assert!(toml::from_str::<ClientConfig>(r#"remap = { 768 = "a" }"#).is_err());
+    }
+
+    #[test]
+    fn device_route_parses() {
+        let route = toml::from_str::<DeviceRouteConfig>(
+            r#"
+            client = "art-workstation"
+            name = "Wacom Intuos Pro M Pen"
+            vendor-id = 1386
+            "#,
+        )
+        .unwrap();
+
+        let device = |vendor| DeviceInfo {
+            name: c"Wacom Intuos Pro M Pen".into(),
+            vendor,
+            product: 0x357,
+            ..Default::default()
+        };
+
+        assert_eq!(route.client, "art-workstation");
+        assert!(route.device.matches(&device(1386)));
+        assert!(!route.device.matches(&device(1387)));
+    }
+
+    #[test]
+    fn device_lists_parse() {
+        let config = toml::from_str::<Config>(
+            r#"
+            listen = "0.0.0.0:5258"
+            switch-keys = ["left-alt", "left-ctrl"]
+            certificate = "/etc/rkvm/certificate.pem"
+            key = "/etc/rkvm/key.pem"
+
+            [[device-allowlist]]
+            bus = "usb"
+
+            [[device-denylist]]
+            name-glob = "Yubico Yubi?ey*"
+
+            [[device-denylist]]
+            class = "gamepad"
+
+            [[device-denylist]]
+            name-regex = "(?i)^logitech"
+            phys = "usb-0000:00:14.0-*"
+            "#,
+        )
+        .unwrap();
+
+        let allowed = |name: &CStr, bus, phys: Option<&CStr>, classes: &[DeviceClass]| {
+            let device = DeviceInfo {
+                name: name.into(),
+                bus: Some(bus),
+                phys: phys.map(Into::into),
+                classes: classes.iter().copied().collect(),
+                ..Default::default()
+            };
+
+            device.allowed(&config.device_allowlist, &config.device_denylist)
+        };
+
+        let keyboard = [DeviceClass::Keyboard];
+
+        assert!(allowed(c"AT Keyboard", Bus::Usb, None, &keyboard));
+        assert!(!allowed(c"AT Keyboard", Bus::I8042, None, &keyboard));
+        assert!(!allowed(
+            c"Yubico YubiKey OTP+FIDO+CCID",
+            Bus::Usb,
+            None,
+            &keyboard
+        ));
+        assert!(!allowed(
+            c"Xbox Controller",
+            Bus::Usb,
+            None,
+            &[DeviceClass::Gamepad]
+        ));
+
+        // Both the name and the physical path have to match.
+        let phys = Some(c"usb-0000:00:14.0-2/input0");
+        assert!(!allowed(
+            c"Logitech USB Receiver",
+            Bus::Usb,
+            phys,
+            &keyboard
+        ));
+        assert!(allowed(c"Logitech USB Receiver", Bus::Usb, None, &keyboard));
+        assert!(allowed(c"Dell Keyboard", Bus::Usb, phys, &keyboard));
+
+        assert!(toml::from_str::<DeviceSpec>(r#"name-regex = "(""#).is_err());
+    }
+
+    #[test]
+    fn clients_match_by_name_then_address() {
+        let clients = [
+            r#"addr = "10.0.0.1""#,
+            "name = \"laptop\"\naddr = \"10.0.0.2\"",
+            r#"name = "desktop""#,
+        ]
+        .map(|client| toml::from_str::<ClientConfig>(client).unwrap());
+
+        let find = |name, addr: &str| find_client(&clients, name, addr.parse().unwrap());
+
+        assert_eq!(find("laptop", "10.0.0.2"), Some(1));
+        // The name wins over another entry's address.
+        assert_eq!(find("laptop", "10.0.0.1"), Some(1));
+        assert_eq!(find("other", "10.0.0.1"), Some(0));
+        // Named entries don't match on their address alone.
+        assert_eq!(find("other", "10.0.0.2"), None);
+        // Entries without an address only match on the name.
+        assert_eq!(find("desktop", "10.0.0.3"), Some(2));
+        assert_eq!(find("other", "10.0.0.3"), None);
+    }
 }
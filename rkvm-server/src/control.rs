@@ -0,0 +1,138 @@
+use rkvm_net::control::{Request, Response};
+use rkvm_net::message::Message;
+use std::fs::{self, DirBuilder, Permissions};
+use std::io::{self, ErrorKind};
+use std::os::unix::fs::{DirBuilderExt, PermissionsExt};
+use std::path::Path;
+use tokio::io::{AsyncWriteExt, BufStream};
+use tokio::net::{UnixListener, UnixStream};
+use tokio::sync::mpsc::Sender;
+use tokio::sync::oneshot;
+
+/// A request along with a way to reply to it, handled by the main loop.
+pub type Command = (Request, oneshot::Sender<Response>);
+
+pub fn listen(path: &Path) -> Result<UnixListener, io::Error> {
+    // Remove a stale socket left over by a previous run.
+    match fs::remove_file(path) {
+        Ok(()) => {}
+        Err(err) if err.kind() == ErrorKind::NotFound => {}
+        Err(err) => return Err(err),
+    }
+
+    // Only the user running the server can control it. The socket is created in a directory nobody else
+    // can access and moved into place once its permissions are restricted, so nobody can connect in between.
+    let name = path
+        .file_name()
+        .ok_or_else(|| io::Error::new(ErrorKind::InvalidInput, "Invalid control socket path"))?;
+
+    let dir = path.with_file_name(format!(
+        ".{}.{}",
+        name.to_string_lossy(),
+        std::process::id()
+    ));
+
+    match fs::remove_dir_all(&dir) {
+        Ok(()) => {}
+        Err(err) if err.kind() == ErrorKind::NotFound => {}
+        Err(err) => return Err(err),
+    }
+
+    DirBuilder::new().mode(0o700).create(&dir)?;
+
+    let bind = || {
+        let temporary = dir.join("socket");
+        let listener = UnixListener::bind(&temporary)?;
+
+        fs::set_permissions(&temporary, Permissions::from_mode(0o600))?;
+        fs::rename(&temporary, path)?;
+
+        Ok(listener)
+    };
+
+    let result = bind();
+    let _ = fs::remove_dir_all(&dir);
+
+    result
+}
+
+pub async fn handle(stream: UnixStream, sender: Sender<Command>) -> Result<(), io::Error> {
+    let mut stream = BufStream::new(stream);
+
+    loop {
+        let request = match Request::decode(&mut stream).await {
+            Ok(request) => request,
+            Err(err) if err.kind() == ErrorKind::UnexpectedEof => return Ok(()),
+            Err(err) => return Err(err),
+        };
+
+        tracing::debug!(request = ?request, "Received control request");
+
+        let (reply, response) = oneshot::channel();
+        if sender.send((request, reply)).await.is_err() {
+            return Ok(());
+        }
+
+        let response = match response.await {
+            Ok(response) => response,
+            Err(_) => return Ok(()),
+        };
+
+        response.encode(&mut stream).await?;
+        stream.flush().await?;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rkvm_net::control::Target;
+    use tokio::sync::mpsc;
+
+    #[tokio::test]
+    async fn socket_is_owner_only() {
+        let dir = std::env::temp_dir().join(format!("rkvm-control-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+
+        let path = dir.join("rkvm-server.sock");
+        let listener = listen(&path).unwrap();
+
+        let mode = fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+
+        // Only the socket is left behind.
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+
+        let _stream = UnixStream::connect(&path).await.unwrap();
+        listener.accept().await.unwrap();
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn replies_to_requests() {
+        let (client, server) = UnixStream::pair().unwrap();
+        let (sender, mut receiver) = mpsc::channel(1);
+
+        let handle = tokio::spawn(handle(server, sender));
+        let mut client = BufStream::new(client);
+
+        for _ in 0..2 {
+            Request::Switch(Target::Name("laptop".to_owned()))
+                .encode(&mut client)
+                .await
+                .unwrap();
+            client.flush().await.unwrap();
+
+            let (request, reply) = receiver.recv().await.unwrap();
+            assert!(matches!(request, Request::Switch(Target::Name(name)) if name == "laptop"));
+            reply.send(Response::Done).unwrap();
+
+            let response = Response::decode(&mut client).await.unwrap();
+            assert!(matches!(response, Response::Done));
+        }
+
+        drop(client);
+        handle.await.unwrap().unwrap();
+    }
+}
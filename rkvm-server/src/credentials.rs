@@ -0,0 +1,197 @@
+use crate::config::Config;
+use std::collections::HashSet;
+use std::net::IpAddr;
+
+struct Entry {
+    name: Option<String>,
+    addr: Option<IpAddr>,
+    password: Option<String>,
+}
+
+/// Decides which password a client has to authenticate with.
+///
+/// Reloaded along with the config, connected clients are expected to disconnect
+/// if their password changes.
+pub struct Credentials {
+    password: Option<String>,
+    clients: Vec<Entry>,
+    revoked: HashSet<String>,
+    // Own passwords of revoked clients, refused whatever name they're used with.
+    revoked_passwords: HashSet<String>,
+}
+
+impl Credentials {
+    pub fn new(config: &Config) -> Self {
+        let clients = config
+            .clients
+            .iter()
+            .map(|client| Entry {
+                name: client.name.clone(),
+                addr: client.addr,
+                password: client.password.clone(),
+            })
+            .collect();
+
+        let mut revoked_passwords = HashSet::new();
+        for name in &config.revoked {
+            let password = config
+                .clients
+                .iter()
+                .find(|client| client.name.as_ref() == Some(name))
+                .and_then(|client| client.password.clone());
+
+            match password {
+                Some(password) => {
+                    revoked_passwords.insert(password);
+                }
+                // The name is chosen by the client, so it could just use another one with the shared password.
+                None => tracing::warn!(
+                    "Revoked client {} has no password of its own, it can still connect under another name",
+                    name
+                ),
+            }
+        }
+
+        Self {
+            password: config.password.clone(),
+            clients,
+            revoked: config.revoked.clone(),
+            revoked_passwords,
+        }
+    }
+
+    /// Returns None if the client is not allowed to connect at all.
+    pub fn password(&self, name: &str, addr: IpAddr) -> Option<&str> {
+        if self.revoked.contains(name) {
+            return None;
+        }
+
+        // Same as when assigning clients to entries, names take precedence.
+        let entry = self
+            .clients
+            .iter()
+            .find(|entry| entry.name.as_deref() == Some(name))
+            .or_else(|| {
+                self.clients
+                    .iter()
+                    .find(|entry| entry.name.is_none() && entry.addr == Some(addr))
+            });
+
+        let password = match entry {
+            Some(Entry {
+                password: Some(password),
+                ..
+            }) => Some(password.as_str()),
+            _ => self.password.as_deref(),
+        };
+
+        password.filter(|password| !self.revoked_passwords.contains(*password))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::net::Ipv4Addr;
+
+    fn credentials(extra: &str) -> Credentials {
+        let config = format!(
+            r#"
+            listen = "0.0.0.0:5258"
+            switch-keys = ["left-alt", "left-ctrl"]
+            certificate = "/etc/rkvm/certificate.pem"
+            key = "/etc/rkvm/key.pem"
+            {}
+
+            [[clients]]
+            name = "laptop"
+            password = "laptop-secret"
+
+            [[clients]]
+            addr = "10.10.0.2"
+            password = "desktop-secret"
+            "#,
+            extra
+        );
+
+        Credentials::new(&toml::from_str(&config).unwrap())
+    }
+
+    const ADDR: IpAddr = IpAddr::V4(Ipv4Addr::new(10, 10, 0, 1));
+
+    #[test]
+    fn per_client_passwords() {
+        let credentials = credentials(r#"password = "shared""#);
+
+        assert_eq!(credentials.password("laptop", ADDR), Some("laptop-secret"));
+        assert_eq!(
+            credentials.password("desktop", "10.10.0.2".parse().unwrap()),
+            Some("desktop-secret")
+        );
+
+        // The name takes precedence over the address.
+        assert_eq!(
+            credentials.password("laptop", "10.10.0.2".parse().unwrap()),
+            Some("laptop-secret")
+        );
+
+        assert_eq!(credentials.password("other", ADDR), Some("shared"));
+    }
+
+    #[test]
+    fn named_entries_need_the_name() {
+        let credentials = credentials(
+            r#"
+            password = "shared"
+
+            [[clients]]
+            name = "couch"
+            addr = "10.10.0.3"
+            password = "couch-secret"
+            "#,
+        );
+
+        let addr = "10.10.0.3".parse().unwrap();
+        assert_eq!(credentials.password("couch", addr), Some("couch-secret"));
+        assert_eq!(credentials.password("other", addr), Some("shared"));
+    }
+
+    #[test]
+    fn no_shared_password() {
+        let credentials = credentials("");
+
+        assert_eq!(credentials.password("laptop", ADDR), Some("laptop-secret"));
+        assert_eq!(credentials.password("other", ADDR), None);
+    }
+
+    #[test]
+    fn revoked() {
+        let credentials = credentials(
+            r#"
+            password = "shared"
+            revoked = ["laptop", "other"]
+            "#,
+        );
+
+        assert_eq!(credentials.password("laptop", ADDR), None);
+        assert_eq!(credentials.password("other", ADDR), None);
+    }
+
+    #[test]
+    fn revoked_password_is_refused_under_other_names() {
+        let credentials = credentials(
+            r#"
+            password = "shared"
+            revoked = ["laptop"]
+
+            [[clients]]
+            name = "spare"
+            password = "laptop-secret"
+            "#,
+        );
+
+        assert_eq!(credentials.password("spare", ADDR), None);
+        // The shared password isn't the revoked client's own, so it's still accepted.
+        assert_eq!(credentials.password("other", ADDR), Some("shared"));
+    }
+}
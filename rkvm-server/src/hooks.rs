@@ -0,0 +1,132 @@
+use crate::config::Config;
+use crate::router::Reason;
+use std::net::SocketAddr;
+use tokio::process::Command;
+
+/// A switch target as seen by hooks.
+#[derive(Clone, Copy, Debug)]
+pub struct Target<'a> {
+    pub idx: usize,
+    // None for the server itself.
+    pub client: Option<(&'a str, SocketAddr)>,
+}
+
+/// User commands run on focus switches and client connection events.
+///
+/// Commands are run with `sh -c` in the background, the server doesn't wait for them to finish.
+pub struct Hooks {
+    on_switch: Option<String>,
+    on_client_connect: Option<String>,
+    on_client_disconnect: Option<String>,
+}
+
+impl Hooks {
+    pub fn new(config: &Config) -> Self {
+        Self {
+            on_switch: config.on_switch.clone(),
+            on_client_connect: config.on_client_connect.clone(),
+            on_client_disconnect: config.on_client_disconnect.clone(),
+        }
+    }
+
+    pub fn switch(&self, previous: Target<'_>, current: Target<'_>, reason: Reason) {
+        if let Some(command) = &self.on_switch {
+            let mut env = vec![("RKVM_REASON".to_owned(), reason_name(reason).to_owned())];
+            env.extend(target_env("RKVM_PREVIOUS", previous));
+            env.extend(target_env("RKVM_CURRENT", current));
+
+            run("on-switch", command, env);
+        }
+    }
+
+    pub fn connect(&self, client: Target<'_>) {
+        if let Some(command) = &self.on_client_connect {
+            run(
+                "on-client-connect",
+                command,
+                target_env("RKVM_CLIENT", client),
+            );
+        }
+    }
+
+    pub fn disconnect(&self, client: Target<'_>) {
+        if let Some(command) = &self.on_client_disconnect {
+            run(
+                "on-client-disconnect",
+                command,
+                target_env("RKVM_CLIENT", client),
+            );
+        }
+    }
+}
+
+fn reason_name(reason: Reason) -> &'static str {
+    match reason {
+        Reason::Keys => "keys",
+        Reason::Taps => "taps",
+        Reason::Wheel => "wheel",
+        Reason::Edge => "edge",
+        Reason::Control => "control",
+        Reason::Disconnect => "disconnect",
+        Reason::Idle => "idle",
+    }
+}
+
+// Name and address variables are left unset for the server.
+fn target_env(prefix: &str, target: Target<'_>) -> Vec<(String, String)> {
+    let mut env = vec![(prefix.to_owned(), target.idx.to_string())];
+    if let Some((name, addr)) = target.client {
+        env.push((format!("{}_NAME", prefix), name.to_owned()));
+        env.push((format!("{}_ADDR", prefix), addr.to_string()));
+    }
+
+    env
+}
+
+fn run(hook: &'static str, command: &str, env: Vec<(String, String)>) {
+    let mut child = match Command::new("sh").arg("-c").arg(command).envs(env).spawn() {
+        Ok(child) => child,
+        Err(err) => {
+            tracing::error!(hook = %hook, "Error running hook: {}", err);
+            return;
+        }
+    };
+
+    tokio::spawn(async move {
+        match child.wait().await {
+            Ok(status) if status.success() => {}
+            Ok(status) => tracing::warn!(hook = %hook, "Hook failed: {}", status),
+            Err(err) => tracing::error!(hook = %hook, "Error waiting for hook: {}", err),
+        }
+    });
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn target_env_for_server_and_client() {
+        let server = Target {
+            idx: 0,
+            client: None,
+        };
+        assert_eq!(
+            target_env("RKVM_CLIENT", server),
+            [("RKVM_CLIENT".to_owned(), "0".to_owned())]
+        );
+
+        let client = Target {
+            idx: 2,
+            client: Some(("laptop", "10.0.0.2:1234".parse().unwrap())),
+        };
+        assert_eq!(
+            target_env("RKVM_CURRENT", client),
+            [
+                ("RKVM_CURRENT".to_owned(), "2".to_owned()),
+                ("RKVM_CURRENT_NAME".to_owned(), "laptop".to_owned()),
+                ("RKVM_CURRENT_ADDR".to_owned(), "10.0.0.2:1234".to_owned()),
+            ]
+        );
+    }
+}
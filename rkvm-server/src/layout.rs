@@ -0,0 +1,340 @@
+use crate::config::{ClientConfig, LayoutConfig, NeighbourConfig};
+use rkvm_input::rel::RelAxis;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("Unknown client {0:?}")]
+    UnknownClient(String),
+    #[error("Screen size must not be zero")]
+    EmptyScreen,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Side {
+    Left,
+    Right,
+    Above,
+    Below,
+}
+
+impl Side {
+    fn opposite(self) -> Self {
+        match self {
+            Self::Left => Self::Right,
+            Self::Right => Self::Left,
+            Self::Above => Self::Below,
+            Self::Below => Self::Above,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Screen {
+    width: i64,
+    height: i64,
+}
+
+impl Screen {
+    fn new(width: u32, height: u32) -> Result<Self, Error> {
+        if width == 0 || height == 0 {
+            return Err(Error::EmptyScreen);
+        }
+
+        Ok(Self {
+            width: width.into(),
+            height: height.into(),
+        })
+    }
+}
+
+struct Neighbour {
+    side: Side,
+    // Target index, that is client index + 1.
+    idx: usize,
+    screen: Screen,
+}
+
+/// Tracks the pointer position across the server screen and its neighbours.
+///
+/// The position is estimated by adding up relative motion, so it will drift
+/// if the display server applies pointer acceleration.
+pub struct Layout {
+    screen: Screen,
+    neighbours: Vec<Neighbour>,
+    x: i64,
+    y: i64,
+}
+
+impl Layout {
+    pub fn new(config: &LayoutConfig, clients: &[ClientConfig]) -> Result<Self, Error> {
+        let sides = [
+            (Side::Left, &config.left),
+            (Side::Right, &config.right),
+            (Side::Above, &config.above),
+            (Side::Below, &config.below),
+        ];
+
+        let mut neighbours = Vec::new();
+        for (side, neighbour) in sides {
+            let NeighbourConfig {
+                client,
+                width,
+                height,
+            } = match neighbour {
+                Some(neighbour) => neighbour,
+                None => continue,
+            };
+
+            let idx = clients
+                .iter()
+                .position(|c| c.name.as_ref() == Some(client))
+                .ok_or_else(|| Error::UnknownClient(client.clone()))?;
+
+            neighbours.push(Neighbour {
+                side,
+                idx: idx + 1,
+                screen: Screen::new(*width, *height)?,
+            });
+        }
+
+        let screen = Screen::new(config.width, config.height)?;
+
+        Ok(Self {
+            screen,
+            neighbours,
+            x: screen.width / 2,
+            y: screen.height / 2,
+        })
+    }
+
+    fn screen(&self, current: usize) -> Option<Screen> {
+        if current == 0 {
+            return Some(self.screen);
+        }
+
+        self.neighbours
+            .iter()
+            .find(|neighbour| neighbour.idx == current)
+            .map(|neighbour| neighbour.screen)
+    }
+
+    /// Moves the tracked pointer to the center of the given target's screen.
+    ///
+    /// Used when the target was switched by other means than crossing an edge.
+    pub fn reset(&mut self, current: usize) {
+        if let Some(screen) = self.screen(current) {
+            self.x = screen.width / 2;
+            self.y = screen.height / 2;
+        }
+    }
+
+    /// Applies relative motion of the pointer on the current target.
+    ///
+    /// If the pointer crossed an edge leading to a target for which `exists` returns true,
+    /// returns that target along with motion along the same axis which pushes the target's
+    /// cursor against the edge it entered through.
+    pub fn motion(
+        &mut self,
+        current: usize,
+        axis: RelAxis,
+        value: i32,
+        exists: impl Fn(usize) -> bool,
+    ) -> Option<(usize, i32)> {
+        let screen = self.screen(current)?;
+
+        let (position, extent, low, high) = match axis {
+            RelAxis::X => (self.x, screen.width, Side::Left, Side::Right),
+            RelAxis::Y => (self.y, screen.height, Side::Above, Side::Below),
+            _ => return None,
+        };
+
+        let position = position + i64::from(value);
+        let clamped = position.clamp(0, extent - 1);
+
+        match axis {
+            RelAxis::X => self.x = clamped,
+            _ => self.y = clamped,
+        }
+
+        let side = if position < 0 {
+            low
+        } else if position >= extent {
+            high
+        } else {
+            return None;
+        };
+
+        let (target, next) = if current == 0 {
+            self.neighbours
+                .iter()
+                .find(|neighbour| neighbour.side == side)
+                .map(|neighbour| (neighbour.idx, neighbour.screen))?
+        } else {
+            self.neighbours
+                .iter()
+                .find(|neighbour| neighbour.idx == current && neighbour.side.opposite() == side)
+                .map(|_| (0, self.screen))?
+        };
+
+        if !exists(target) {
+            return None;
+        }
+
+        // Enter through the opposite edge and keep the relative position along it.
+        let push = match side {
+            Side::Left => {
+                self.x = next.width - 1;
+                self.y = self.y * next.height / screen.height;
+                next.width
+            }
+            Side::Right => {
+                self.x = 0;
+                self.y = self.y * next.height / screen.height;
+                -next.width
+            }
+            Side::Above => {
+                self.y = next.height - 1;
+                self.x = self.x * next.width / screen.width;
+                next.height
+            }
+            Side::Below => {
+                self.y = 0;
+                self.x = self.x * next.width / screen.width;
+                -next.height
+            }
+        };
+
+        Some((target, push.clamp(i32::MIN as _, i32::MAX as _) as i32))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::config::Config;
+
+    fn layout() -> Layout {
+        let config = toml::from_str::<Config>(
+            r#"
+            listen = "0.0.0.0:5258"
+            switch-keys = ["left-alt", "left-ctrl"]
+            certificate = "/etc/rkvm/certificate.pem"
+            key = "/etc/rkvm/key.pem"
+            password = "123456789"
+
+            [layout]
+            width = 1000
+            height = 1000
+            left = { client = "laptop", width = 500, height = 250 }
+
+            [[clients]]
+            addr = "10.10.0.1"
+
+            [[clients]]
+            addr = "10.10.0.2"
+            name = "laptop"
+            "#,
+        )
+        .unwrap();
+
+        Layout::new(config.layout.as_ref().unwrap(), &config.clients).unwrap()
+    }
+
+    #[test]
+    fn crosses_to_neighbour() {
+        let mut layout = layout();
+
+        assert_eq!(layout.motion(0, RelAxis::X, -499, |_| true), None);
+        assert_eq!(layout.motion(0, RelAxis::X, -2, |_| true), Some((2, 500)));
+
+        // Entered at the right edge, vertical position is scaled.
+        assert_eq!(layout.x, 499);
+        assert_eq!(layout.y, 125);
+    }
+
+    #[test]
+    fn crosses_back_to_server() {
+        let mut layout = layout();
+
+        layout.motion(0, RelAxis::X, -1000, |_| true);
+        assert_eq!(layout.motion(2, RelAxis::X, 1, |_| true), Some((0, -1000)));
+        assert_eq!(layout.x, 0);
+        assert_eq!(layout.y, 500);
+    }
+
+    #[test]
+    fn stops_at_edge_without_neighbour() {
+        let mut layout = layout();
+
+        assert_eq!(layout.motion(0, RelAxis::X, 1000, |_| true), None);
+        assert_eq!(layout.x, 999);
+
+        assert_eq!(layout.motion(0, RelAxis::Y, -1000, |_| true), None);
+        assert_eq!(layout.y, 0);
+
+        // Moving back works immediately, the position doesn't go past the edge.
+        assert_eq!(layout.motion(0, RelAxis::X, -1, |_| true), None);
+        assert_eq!(layout.x, 998);
+    }
+
+    #[test]
+    fn skips_disconnected_neighbour() {
+        let mut layout = layout();
+
+        assert_eq!(layout.motion(0, RelAxis::X, -1000, |idx| idx == 0), None);
+        assert_eq!(layout.x, 0);
+    }
+
+    #[test]
+    fn huge_push_is_clamped() {
+        let config = toml::from_str::<LayoutConfig>(
+            r#"
+            width = 1000
+            height = 1000
+            right = { client = "desktop", width = 3000000000, height = 1000 }
+            "#,
+        )
+        .unwrap();
+
+        let clients = toml::from_str::<Config>(
+            r#"
+            listen = "0.0.0.0:5258"
+            switch-keys = ["left-alt", "left-ctrl"]
+            certificate = "/etc/rkvm/certificate.pem"
+            key = "/etc/rkvm/key.pem"
+
+            [[clients]]
+            addr = "10.10.0.1"
+            name = "desktop"
+            "#,
+        )
+        .unwrap()
+        .clients;
+
+        let mut layout = Layout::new(&config, &clients).unwrap();
+
+        // The cursor is pushed towards the left edge of the client, not the right one.
+        assert_eq!(
+            layout.motion(0, RelAxis::X, 1000, |_| true),
+            Some((1, i32::MIN))
+        );
+    }
+
+    #[test]
+    fn unknown_client() {
+        let config = toml::from_str::<LayoutConfig>(
+            r#"
+            width = 1000
+            height = 1000
+            right = { client = "desktop", width = 500, height = 250 }
+            "#,
+        )
+        .unwrap();
+
+        assert!(matches!(
+            Layout::new(&config, &[]),
+            Err(Error::UnknownClient(client)) if client == "desktop"
+        ));
+    }
+}
@@ -0,0 +1,105 @@
+use crate::config::{MacroConfig, MacroStep};
+use rkvm_input::event::Event;
+use rkvm_input::key::KeyEvent;
+use rkvm_input::sync::SyncEvent;
+use std::time::Duration;
+use tokio::sync::mpsc::Sender;
+use tokio::time;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Step {
+    Key(KeyEvent),
+    Sleep(Duration),
+}
+
+/// A sequence of key events played back on the focused target.
+pub struct Macro {
+    steps: Vec<Step>,
+}
+
+impl Macro {
+    pub fn new(config: &MacroConfig) -> Self {
+        let steps = config
+            .steps
+            .iter()
+            .map(|step| match step {
+                MacroStep::Press(key) => Step::Key(KeyEvent {
+                    key: (*key).into(),
+                    down: true,
+                }),
+                MacroStep::Release(key) => Step::Key(KeyEvent {
+                    key: (*key).into(),
+                    down: false,
+                }),
+                MacroStep::Sleep(millis) => Step::Sleep(Duration::from_millis(*millis)),
+            })
+            .collect();
+
+        Self { steps }
+    }
+
+    /// Sends the events as if they came from `device`, stopping early if the receiver is gone.
+    pub async fn run<T: Copy>(&self, device: T, sender: Sender<(T, Event)>) {
+        for step in &self.steps {
+            let events = match step {
+                Step::Key(event) => [Event::Key(*event), Event::Sync(SyncEvent::All)],
+                Step::Sleep(duration) => {
+                    time::sleep(*duration).await;
+                    continue;
+                }
+            };
+
+            for event in events {
+                if sender.send((device, event)).await.is_err() {
+                    return;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rkvm_input::key::{Key, Keyboard};
+    use tokio::sync::mpsc;
+
+    #[tokio::test]
+    async fn runs_steps() {
+        let config = toml::from_str::<MacroConfig>(
+            r#"
+            keys = ["left-alt", "f9"]
+            steps = [
+                { press = "left-ctrl" },
+                { sleep = 10 },
+                { release = "left-ctrl" },
+            ]
+            "#,
+        )
+        .unwrap();
+
+        let r#macro = Macro::new(&config);
+        assert_eq!(r#macro.steps[1], Step::Sleep(Duration::from_millis(10)));
+
+        let (sender, mut receiver) = mpsc::channel(8);
+        r#macro.run(3, sender).await;
+
+        let key = |down| {
+            Event::Key(KeyEvent {
+                key: Key::Key(Keyboard::LeftCtrl),
+                down,
+            })
+        };
+
+        for expected in [
+            key(true),
+            Event::Sync(SyncEvent::All),
+            key(false),
+            Event::Sync(SyncEvent::All),
+        ] {
+            assert_eq!(receiver.recv().await, Some((3, expected)));
+        }
+
+        assert_eq!(receiver.recv().await, None);
+    }
+}
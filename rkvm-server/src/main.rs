@@ -1,13 +1,26 @@
 mod config;
+mod control;
+mod credentials;
+mod hooks;
+mod layout;
+mod macros;
+mod pointer;
+mod router;
 mod server;
 mod tls;
+mod transform;
 
 use clap::Parser;
 use config::Config;
+use credentials::Credentials;
+use router::Router;
 use std::future;
 use std::path::PathBuf;
 use std::process::ExitCode;
+use std::sync::Arc;
 use std::time::Duration;
+use tokio::signal::unix::{self, SignalKind};
+use tokio::sync::watch;
 use tokio::{fs, signal, time};
 use tracing::subscriber;
 use tracing_subscriber::filter::{EnvFilter, LevelFilter};
@@ -67,12 +80,51 @@ async fn main() -> ExitCode {
         }
     };
 
-    let switch_keys = config.switch_keys.into_iter().map(Into::into).collect();
-    let server_goto_keys = config.goto_keys.map(|keys_vec| keys_vec.into_iter().map(Into::into).collect());
-    let propagate_switch_keys = config.propagate_switch_keys.unwrap_or(true);
+    let router = match Router::new(&config) {
+        Ok(router) => router,
+        Err(err) => {
+            tracing::error!("Error configuring switching: {}", err);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let (credentials_sender, credentials) = watch::channel(Arc::new(Credentials::new(&config)));
+
+    let mut hangup = match unix::signal(SignalKind::hangup()) {
+        Ok(hangup) => hangup,
+        Err(err) => {
+            tracing::error!("Error setting up signal handler: {}", err);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    // Only credentials are reloaded, other changes require a restart.
+    let config_path = args.config_path.clone();
+    tokio::spawn(async move {
+        while hangup.recv().await.is_some() {
+            let config = match fs::read_to_string(&config_path).await {
+                Ok(config) => config,
+                Err(err) => {
+                    tracing::error!("Error reading config: {}", err);
+                    continue;
+                }
+            };
+
+            let config = match toml::from_str::<Config>(&config) {
+                Ok(config) => config,
+                Err(err) => {
+                    tracing::error!("Error parsing config: {}", err);
+                    continue;
+                }
+            };
+
+            credentials_sender.send_replace(Arc::new(Credentials::new(&config)));
+            tracing::info!("Reloaded client passwords");
+        }
+    });
 
     tokio::select! {
-        result = server::run(config.listen, acceptor, &config.password, &switch_keys, propagate_switch_keys, &server_goto_keys, &config.clients, config.device_allowlist) => {
+        result = server::run(&config, acceptor, router, credentials) => {
             if let Err(err) = result {
                 tracing::error!("Error: {}", err);
                 return ExitCode::FAILURE;
@@ -0,0 +1,85 @@
+use crate::config::PointerConfig;
+use rkvm_input::rel::RelAxis;
+use std::collections::HashMap;
+
+/// Pointer settings of a client, applied by the router so that the layout sees the motion the client gets.
+pub struct Pointer {
+    sensitivity: f64,
+    acceleration: f64,
+    wheel_scale: f64,
+    invert_wheel: bool,
+    invert_hwheel: bool,
+    // Fractional parts of scaled values not sent yet, per axis.
+    remainders: HashMap<RelAxis, f64>,
+}
+
+impl Pointer {
+    pub fn new(config: &PointerConfig) -> Self {
+        Self {
+            sensitivity: config.sensitivity.unwrap_or(1.0),
+            acceleration: config.acceleration.unwrap_or(0.0),
+            wheel_scale: config.wheel_scale.unwrap_or(1.0),
+            invert_wheel: config.invert_wheel.unwrap_or(false),
+            invert_hwheel: config.invert_hwheel.unwrap_or(false),
+            remainders: HashMap::new(),
+        }
+    }
+
+    /// Returns the scaled value, None if the motion is too small to be sent yet.
+    pub fn rel(&mut self, axis: RelAxis, value: i32) -> Option<i32> {
+        let invert = |invert| if invert { -1.0 } else { 1.0 };
+
+        let factor = match axis {
+            // The faster the motion, the further it goes.
+            RelAxis::X | RelAxis::Y => {
+                self.sensitivity * (1.0 + self.acceleration * f64::from(value.unsigned_abs()))
+            }
+            RelAxis::Wheel | RelAxis::WheelHiRes => self.wheel_scale * invert(self.invert_wheel),
+            RelAxis::HWheel | RelAxis::HWheelHiRes => self.wheel_scale * invert(self.invert_hwheel),
+            _ => return Some(value),
+        };
+
+        let remainder = self.remainders.entry(axis).or_default();
+        let total = f64::from(value) * factor + *remainder;
+        let whole = total.trunc();
+        *remainder = total - whole;
+
+        // Float to int casts saturate.
+        match whole as i32 {
+            0 => None,
+            value => Some(value),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn scales_pointer_motion() {
+        let config = toml::from_str::<PointerConfig>(
+            r#"
+            sensitivity = 0.5
+            invert-wheel = true
+            wheel-scale = 2.0
+            "#,
+        )
+        .unwrap();
+
+        let mut pointer = Pointer::new(&config);
+        let mut rel = |axis, value| pointer.rel(axis, value);
+
+        assert_eq!(rel(RelAxis::X, 4), Some(2));
+        // Slow motion is accumulated rather than lost.
+        assert_eq!(rel(RelAxis::Y, 1), None);
+        assert_eq!(rel(RelAxis::Y, 1), Some(1));
+        assert_eq!(rel(RelAxis::X, -3), Some(-1));
+        assert_eq!(rel(RelAxis::X, -1), Some(-1));
+
+        assert_eq!(rel(RelAxis::Wheel, 1), Some(-2));
+        assert_eq!(rel(RelAxis::WheelHiRes, 120), Some(-240));
+        assert_eq!(rel(RelAxis::HWheel, 1), Some(2));
+        assert_eq!(rel(RelAxis::Dial, 1), Some(1));
+    }
+}
@@ -0,0 +1,1992 @@
+use crate::config::Config;
+use crate::layout::{self, Layout};
+use crate::pointer::Pointer;
+use rkvm_input::device::{DeviceInfo, DeviceSpec};
+use rkvm_input::event::Event;
+use rkvm_input::key::{Key, KeyEvent, Keyboard};
+use rkvm_input::rel::{RelAxis, RelEvent};
+use rkvm_input::sync::SyncEvent;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::time::{Duration, Instant};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("Unknown client {0:?}")]
+    UnknownClient(String),
+    #[error("Invalid layout: {0}")]
+    Layout(#[from] layout::Error),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Reason {
+    Keys,
+    Taps,
+    Wheel,
+    Edge,
+    Control,
+    Disconnect,
+    Idle,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Change {
+    Switched {
+        previous: usize,
+        current: usize,
+        reason: Reason,
+    },
+    BroadcastStarted {
+        group: usize,
+    },
+    BroadcastStopped {
+        group: usize,
+    },
+    /// The macro should be run on behalf of the given device.
+    Macro {
+        idx: usize,
+        id: usize,
+    },
+}
+
+/// What to do with an event read from a device.
+#[derive(Debug, PartialEq)]
+pub struct Route {
+    /// Targets to write the events to, index 0 being the server and N being client N - 1.
+    pub targets: Vec<usize>,
+    pub events: Vec<Event>,
+    /// Events generated by the router, written after the ones above.
+    pub synthetic: Vec<Synthetic>,
+    pub changes: Vec<Change>,
+}
+
+/// An event not read from a device, but written as if it came from device `id`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Synthetic {
+    pub target: usize,
+    pub id: usize,
+    pub event: Event,
+}
+
+const MODIFIERS: [Keyboard; 8] = [
+    Keyboard::LeftShift,
+    Keyboard::RightShift,
+    Keyboard::LeftCtrl,
+    Keyboard::RightCtrl,
+    Keyboard::LeftAlt,
+    Keyboard::RightAlt,
+    Keyboard::LeftMeta,
+    Keyboard::RightMeta,
+];
+
+struct BroadcastGroup {
+    keys: HashSet<Key>,
+    // None means all connected clients.
+    targets: Option<Vec<usize>>,
+}
+
+struct TapTrigger {
+    key: Key,
+    count: usize,
+    window: Duration,
+    // None means cycling like the switch keys.
+    target: Option<usize>,
+}
+
+// The sequence of taps currently in progress.
+struct Taps {
+    key: Key,
+    count: usize,
+    start: Instant,
+    down: bool,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+struct Focus {
+    target: usize,
+    // Broadcast group replacing the target, if any.
+    broadcast: Option<usize>,
+}
+
+/// Decides where events go, implementing the switching logic.
+///
+/// Targets are indexed the same way as in [`Route::targets`].
+pub struct Router {
+    switch_keys: HashSet<Key>,
+    propagate_switch_keys: bool,
+    carry_modifiers: bool,
+    goto_keys: Vec<(HashSet<Key>, usize)>,
+    wheel_switch_keys: Option<HashSet<Key>>,
+    broadcast_groups: Vec<BroadcastGroup>,
+    macros: Vec<HashSet<Key>>,
+    tap_triggers: Vec<TapTrigger>,
+    device_routes: Vec<(DeviceSpec, usize)>,
+    layout: Option<Layout>,
+    // Per target, clients without pointer settings get motion unchanged.
+    pointers: HashMap<usize, Pointer>,
+    all_switch_keys: HashSet<Key>,
+    // Per target, clients without their own timeout use the global one.
+    idle_timeouts: HashMap<usize, Duration>,
+    idle_timeout: Option<Duration>,
+    idle_fallback: usize,
+
+    // Devices and the target they are pinned to, if any.
+    devices: HashMap<usize, Option<usize>>,
+    // Connected clients (the server is always connected).
+    connected: BTreeSet<usize>,
+    pressed_keys: HashSet<Key>,
+    // Keys held down on each target, along with the device they came from.
+    held: HashMap<usize, HashSet<(usize, Key)>>,
+    focus: Focus,
+    // Where switch key releases go after a switch.
+    previous: Focus,
+    changed: bool,
+    // Macro to run once its keys are released.
+    pending_macro: Option<usize>,
+    taps: Option<Taps>,
+    // When the last event not coming from a pinned device was routed.
+    activity: Instant,
+}
+
+impl Router {
+    pub fn new(config: &Config) -> Result<Self, Error> {
+        let target = |name: &String| {
+            config
+                .clients
+                .iter()
+                .position(|client| client.name.as_ref() == Some(name))
+                .map(|idx| idx + 1)
+                .ok_or_else(|| Error::UnknownClient(name.clone()))
+        };
+
+        let keys = |keys: &HashSet<_>| keys.iter().copied().map(Into::into).collect();
+
+        let switch_keys: HashSet<Key> = keys(&config.switch_keys);
+        let mut all_switch_keys = switch_keys.clone();
+
+        let mut goto_keys = Vec::new();
+        if let Some(server_keys) = &config.goto_keys {
+            goto_keys.push((keys(server_keys), 0));
+        }
+
+        for (idx, client) in config.clients.iter().enumerate() {
+            if let Some(client_keys) = &client.goto_keys {
+                goto_keys.push((keys(client_keys), idx + 1));
+            }
+        }
+
+        let mut broadcast_groups = Vec::new();
+        for group in &config.broadcast_groups {
+            let targets = match &group.clients {
+                Some(names) => Some(names.iter().map(target).collect::<Result<_, _>>()?),
+                None => None,
+            };
+
+            broadcast_groups.push(BroadcastGroup {
+                keys: keys(&group.keys),
+                targets,
+            });
+        }
+
+        let macros = config
+            .macros
+            .iter()
+            .map(|r#macro| keys(&r#macro.keys))
+            .collect::<Vec<_>>();
+
+        let tap_triggers = config
+            .tap_triggers
+            .iter()
+            .map(|trigger| {
+                Ok(TapTrigger {
+                    key: trigger.key.into(),
+                    count: trigger.count.get(),
+                    window: Duration::from_millis(trigger.window.unwrap_or(300)),
+                    target: trigger.client.as_ref().map(target).transpose()?,
+                })
+            })
+            .collect::<Result<_, Error>>()?;
+
+        let device_routes = config
+            .device_routes
+            .iter()
+            .map(|route| Ok((route.device.clone(), target(&route.client)?)))
+            .collect::<Result<_, Error>>()?;
+
+        let layout = config
+            .layout
+            .as_ref()
+            .map(|layout| Layout::new(layout, &config.clients))
+            .transpose()?;
+
+        for (keys, _) in &goto_keys {
+            all_switch_keys.extend(keys);
+        }
+
+        for group in &broadcast_groups {
+            all_switch_keys.extend(&group.keys);
+        }
+
+        for keys in &macros {
+            all_switch_keys.extend(keys);
+        }
+
+        let wheel_switch_keys = config.wheel_switch_keys.as_ref().map(keys);
+        if let Some(keys) = &wheel_switch_keys {
+            all_switch_keys.extend(keys);
+        }
+
+        let idle_timeouts = config
+            .clients
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, client)| Some((idx + 1, Duration::from_secs(client.idle_timeout?))))
+            .collect();
+
+        let pointers = config
+            .clients
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, client)| Some((idx + 1, Pointer::new(client.pointer.as_ref()?))))
+            .collect();
+
+        let idle_fallback = config.idle_fallback.as_ref().map(target).transpose()?;
+
+        let focus = Focus {
+            target: 0,
+            broadcast: None,
+        };
+
+        Ok(Self {
+            switch_keys,
+            propagate_switch_keys: config.propagate_switch_keys.unwrap_or(true),
+            carry_modifiers: config.carry_modifiers.unwrap_or(false),
+            goto_keys,
+            wheel_switch_keys,
+            broadcast_groups,
+            macros,
+            tap_triggers,
+            device_routes,
+            layout,
+            pointers,
+            all_switch_keys,
+            idle_timeouts,
+            idle_timeout: config.idle_timeout.map(Duration::from_secs),
+            idle_fallback: idle_fallback.unwrap_or(0),
+            devices: HashMap::new(),
+            connected: BTreeSet::new(),
+            pressed_keys: HashSet::new(),
+            held: HashMap::new(),
+            focus,
+            previous: focus,
+            changed: false,
+            pending_macro: None,
+            taps: None,
+            activity: Instant::now(),
+        })
+    }
+
+    /// Registers a new device, returning the target it's pinned to, if any.
+    pub fn register_device(&mut self, id: usize, device: &DeviceInfo) -> Option<usize> {
+        let pinned = self
+            .device_routes
+            .iter()
+            .find(|(spec, _)| spec.matches(device))
+            .map(|(_, target)| *target);
+
+        self.devices.insert(id, pinned);
+        pinned
+    }
+
+    pub fn unregister_device(&mut self, id: usize) {
+        self.devices.remove(&id);
+
+        for held in self.held.values_mut() {
+            held.retain(|(device, _)| *device != id);
+        }
+    }
+
+    /// Returns the target the device is pinned to, if any.
+    ///
+    /// Pinned devices should not be created on other clients at all.
+    pub fn pinned(&self, id: usize) -> Option<usize> {
+        self.devices.get(&id).copied().flatten()
+    }
+
+    pub fn connect(&mut self, target: usize) {
+        self.connected.insert(target);
+    }
+
+    pub fn disconnect(&mut self, target: usize) -> Vec<Change> {
+        let mut changes = Vec::new();
+
+        self.connected.remove(&target);
+        self.held.remove(&target);
+
+        // Pending switch key releases go to the server instead, a client reusing the index never saw the presses.
+        if self.previous.target == target {
+            self.previous = Focus {
+                target: 0,
+                broadcast: None,
+            };
+        }
+
+        if self.focus.target == target {
+            self.switch(0, Reason::Disconnect, &mut changes);
+        }
+
+        changes
+    }
+
+    /// Returns the focused target, ignoring broadcasting.
+    pub fn current(&self) -> usize {
+        self.focus.target
+    }
+
+    /// Switches to the target as requested from outside, returning None if it's not connected.
+    pub fn goto(&mut self, target: usize) -> Option<Route> {
+        if !self.exists(target) {
+            return None;
+        }
+
+        Some(self.control(target, Reason::Control))
+    }
+
+    /// Switches to the next target as requested from outside.
+    pub fn cycle(&mut self) -> Route {
+        self.control(self.next(), Reason::Control)
+    }
+
+    /// Returns when the focused client becomes idle, if it has an idle timeout.
+    ///
+    /// The server itself never becomes idle.
+    pub fn idle_deadline(&self) -> Option<Instant> {
+        if self.focus.target == 0 || self.focus.target == self.fallback() {
+            return None;
+        }
+
+        let timeout = self
+            .idle_timeouts
+            .get(&self.focus.target)
+            .copied()
+            .or(self.idle_timeout)
+            // A timeout of zero disables it.
+            .filter(|timeout| !timeout.is_zero())?;
+
+        Some(self.activity + timeout)
+    }
+
+    /// Switches to the idle fallback target, to be called once the deadline returned by
+    /// [`Router::idle_deadline`] has passed.
+    pub fn idle(&mut self) -> Route {
+        self.control(self.fallback(), Reason::Idle)
+    }
+
+    // The server is used while the fallback client is not connected.
+    fn fallback(&self) -> usize {
+        if self.exists(self.idle_fallback) {
+            self.idle_fallback
+        } else {
+            0
+        }
+    }
+
+    fn control(&mut self, target: usize, reason: Reason) -> Route {
+        let before = self.focus;
+        let mut changes = Vec::new();
+
+        self.activity = Instant::now();
+        self.switch(target, reason, &mut changes);
+
+        Route {
+            targets: Vec::new(),
+            events: Vec::new(),
+            synthetic: self.release(before),
+            changes,
+        }
+    }
+
+    fn exists(&self, target: usize) -> bool {
+        target == 0 || self.connected.contains(&target)
+    }
+
+    pub fn route(&mut self, id: usize, event: Event) -> Route {
+        self.route_at(id, event, Instant::now())
+    }
+
+    fn route_at(&mut self, id: usize, mut event: Event, now: Instant) -> Route {
+        let mut changes = Vec::new();
+
+        // Pinned devices always go to their client and never take part in switching.
+        if let Some(target) = self.pinned(id) {
+            // Fall back to the server while the client is not connected.
+            let target = if self.exists(target) { target } else { 0 };
+            let (targets, synthetic) = self.scale(id, event, vec![target]);
+
+            return Route {
+                targets,
+                events: vec![event],
+                synthetic,
+                changes,
+            };
+        }
+
+        self.activity = now;
+
+        // Repeats go wherever the key is held and never take part in switching.
+        if let Event::Repeat(key) = event {
+            let mut targets = self
+                .held
+                .iter()
+                .filter(|(_, held)| held.contains(&(id, key)))
+                .map(|(target, _)| *target)
+                .collect::<Vec<_>>();
+
+            targets.sort_unstable();
+
+            return Route {
+                targets,
+                events: vec![event],
+                synthetic: Vec::new(),
+                changes,
+            };
+        }
+
+        let mut press = false;
+
+        if let Event::Key(KeyEvent { key, down }) = event {
+            if self.all_switch_keys.contains(&key) {
+                press = true;
+
+                match down {
+                    true => self.pressed_keys.insert(key),
+                    false => self.pressed_keys.remove(&key),
+                };
+            }
+        }
+
+        let before = self.focus;
+
+        // Who to send this event to.
+        let mut focus = self.focus;
+
+        if press {
+            // If we switched during a previous event, key releases should go to the previous target.
+            if self.changed {
+                focus = self.previous;
+
+                if self.pressed_keys.is_empty() {
+                    self.changed = false;
+
+                    // Run only now, so that the macro's keys don't mix with the ones that triggered it.
+                    if let Some(idx) = self.pending_macro.take() {
+                        changes.push(Change::Macro { idx, id });
+                    }
+                }
+            } else {
+                self.chord(&mut changes);
+            }
+        }
+
+        if let Event::Key(KeyEvent { key, down }) = event {
+            self.tap(key, down, now, &mut changes);
+        }
+
+        let wheel = self.wheel(event, &mut changes);
+
+        // Scaled before the layout tracks it, as the client's screen is what the motion moves across.
+        let mut too_small = false;
+        if let (None, Event::Rel(RelEvent { axis, value })) = (focus.broadcast, &mut event) {
+            if let Some(pointer) = self.pointers.get_mut(&focus.target) {
+                match pointer.rel(*axis, *value) {
+                    Some(scaled) => *value = scaled,
+                    None => too_small = true,
+                }
+            }
+        }
+
+        if let (None, false, Some(layout), Event::Rel(RelEvent { axis, value })) =
+            (focus.broadcast, too_small, &mut self.layout, &mut event)
+        {
+            let connected = &self.connected;
+            let exists = |target| target == 0 || connected.contains(&target);
+
+            if let Some((target, push)) = layout.motion(self.focus.target, *axis, *value, exists) {
+                // The motion that crossed the edge is replaced by one placing the cursor at the entry edge,
+                // it's not scaled so that it gets there.
+                *value = push;
+
+                changes.push(Change::Switched {
+                    previous: self.focus.target,
+                    current: target,
+                    reason: Reason::Edge,
+                });
+
+                self.focus.target = target;
+                focus.target = target;
+            }
+        }
+
+        let (targets, events, mut synthetic) =
+            if wheel || too_small || (press && !self.propagate_switch_keys) {
+                (Vec::new(), Vec::new(), Vec::new())
+            } else {
+                let events = [event]
+                    .into_iter()
+                    .chain(press.then_some(Event::Sync(SyncEvent::All)))
+                    .collect();
+
+                // Without broadcasting, motion was already scaled above.
+                let (targets, synthetic) = match focus.broadcast {
+                    Some(_) => self.scale(id, event, self.targets(focus)),
+                    None => (self.targets(focus), Vec::new()),
+                };
+
+                (targets, events, synthetic)
+            };
+
+        self.track(id, event, &targets);
+
+        // This has to happen after the event itself was accounted for,
+        // as the keys completing a chord are pressed on the previous target.
+        synthetic.extend(self.release(before));
+
+        Route {
+            targets,
+            events,
+            synthetic,
+            changes,
+        }
+    }
+
+    /// Routes an event generated by the server (such as by a macro) to the focused target,
+    /// as if it came from device `id`.
+    ///
+    /// Unlike [`Router::route`], this never switches.
+    pub fn inject(&mut self, id: usize, event: Event) -> Route {
+        // The device might have been removed in the meantime.
+        let targets = if self.devices.contains_key(&id) {
+            self.targets(self.focus)
+        } else {
+            Vec::new()
+        };
+
+        self.track(id, event, &targets);
+
+        Route {
+            targets,
+            events: vec![event],
+            synthetic: Vec::new(),
+            changes: Vec::new(),
+        }
+    }
+
+    fn track(&mut self, id: usize, event: Event, targets: &[usize]) {
+        if let Event::Key(KeyEvent { key, down }) = event {
+            for target in targets {
+                let held = self.held.entry(*target).or_default();
+
+                match down {
+                    true => held.insert((id, key)),
+                    false => held.remove(&(id, key)),
+                };
+            }
+        }
+    }
+
+    /// Releases keys held on targets that lost focus, optionally pressing modifiers on the ones gaining it.
+    fn release(&mut self, before: Focus) -> Vec<Synthetic> {
+        if before == self.focus {
+            return Vec::new();
+        }
+
+        let old = self.targets(before);
+        let new = self.targets(self.focus);
+
+        let mut synthetic = Vec::new();
+        let mut carried = HashSet::new();
+
+        for target in old.iter().copied().filter(|target| !new.contains(target)) {
+            let held = match self.held.remove(&target) {
+                Some(held) => held,
+                None => continue,
+            };
+
+            carried.extend(held.iter().copied().filter(|(_, key)| {
+                self.carry_modifiers
+                    && !self.all_switch_keys.contains(key)
+                    && MODIFIERS.iter().any(|modifier| Key::Key(*modifier) == *key)
+            }));
+
+            synthetic.extend(Self::synthesize(target, &held, false));
+        }
+
+        for target in new.iter().copied().filter(|target| !old.contains(target)) {
+            self.held.entry(target).or_default().extend(&carried);
+            synthetic.extend(Self::synthesize(target, &carried, true));
+        }
+
+        synthetic
+    }
+
+    // Generates key events followed by a sync for each device.
+    fn synthesize(target: usize, keys: &HashSet<(usize, Key)>, down: bool) -> Vec<Synthetic> {
+        let ids = keys.iter().map(|(id, _)| *id).collect::<BTreeSet<_>>();
+
+        let mut synthetic = Vec::new();
+        for id in ids {
+            let events = keys
+                .iter()
+                .filter(|(device, _)| *device == id)
+                .map(|(_, key)| Event::Key(KeyEvent { key: *key, down }))
+                .chain([Event::Sync(SyncEvent::All)]);
+
+            synthetic.extend(events.map(|event| Synthetic { target, id, event }));
+        }
+
+        synthetic
+    }
+
+    fn chord(&mut self, changes: &mut Vec<Change>) {
+        let previous = self.focus;
+
+        let goto = self
+            .goto_keys
+            .iter()
+            .find(|(keys, target)| self.exists(*target) && keys.is_subset(&self.pressed_keys))
+            .map(|(_, target)| *target);
+
+        if let Some(target) = goto {
+            self.switch(target, Reason::Keys, changes);
+        } else if let Some(group) = self
+            .broadcast_groups
+            .iter()
+            .position(|group| group.keys.is_subset(&self.pressed_keys))
+        {
+            // The same keys toggle broadcasting off again.
+            if self.focus.broadcast == Some(group) {
+                self.focus.broadcast = None;
+                changes.push(Change::BroadcastStopped { group });
+            } else {
+                if let Some(group) = self.focus.broadcast {
+                    changes.push(Change::BroadcastStopped { group });
+                }
+
+                self.focus.broadcast = Some(group);
+                changes.push(Change::BroadcastStarted { group });
+            }
+        } else if let Some(idx) = self
+            .macros
+            .iter()
+            .position(|keys| keys.is_subset(&self.pressed_keys))
+        {
+            self.pending_macro = Some(idx);
+        } else if self.switch_keys.is_subset(&self.pressed_keys) {
+            self.switch(self.next(), Reason::Keys, changes);
+        } else {
+            return;
+        }
+
+        self.previous = previous;
+        self.changed = true;
+    }
+
+    // Tracks taps of the same key with nothing else pressed in between, switching once a trigger's
+    // count is reached within its window. The keys themselves are propagated as usual.
+    fn tap(&mut self, key: Key, down: bool, now: Instant, changes: &mut Vec<Change>) {
+        let window = self
+            .tap_triggers
+            .iter()
+            .filter(|trigger| trigger.key == key)
+            .map(|trigger| trigger.window)
+            .max();
+
+        let window = match window {
+            Some(window) => window,
+            None => {
+                // Any other key interrupts the sequence.
+                if down {
+                    self.taps = None;
+                }
+
+                return;
+            }
+        };
+
+        let taps = match &mut self.taps {
+            Some(taps) if taps.key == key && now.duration_since(taps.start) <= window => taps,
+            taps => {
+                if down {
+                    *taps = Some(Taps {
+                        key,
+                        count: 0,
+                        start: now,
+                        down,
+                    });
+                }
+
+                return;
+            }
+        };
+
+        if down {
+            taps.down = true;
+            return;
+        }
+
+        if !taps.down {
+            return;
+        }
+
+        taps.down = false;
+        taps.count += 1;
+
+        let elapsed = now.duration_since(taps.start);
+        let target = self
+            .tap_triggers
+            .iter()
+            .find(|trigger| {
+                trigger.key == key && trigger.count == taps.count && elapsed <= trigger.window
+            })
+            .map(|trigger| trigger.target);
+
+        let target = match target {
+            Some(Some(target)) if self.exists(target) => target,
+            Some(Some(_)) => return,
+            Some(None) => self.next(),
+            None => return,
+        };
+
+        self.taps = None;
+        self.switch(target, Reason::Taps, changes);
+    }
+
+    // Scrolling while the wheel switch keys are held cycles through targets, returns whether
+    // the event should be swallowed.
+    fn wheel(&mut self, event: Event, changes: &mut Vec<Change>) -> bool {
+        let held = self
+            .wheel_switch_keys
+            .as_ref()
+            .is_some_and(|keys| !keys.is_empty() && keys.is_subset(&self.pressed_keys));
+
+        let value = match event {
+            Event::Rel(RelEvent {
+                axis: RelAxis::Wheel,
+                value,
+            }) if held => value,
+            // The high resolution events accompany the regular ones, swallow them too.
+            Event::Rel(RelEvent {
+                axis: RelAxis::WheelHiRes,
+                ..
+            }) => return held,
+            _ => return false,
+        };
+
+        let target = match value {
+            ..=-1 => self.next(),
+            1.. => self.prev(),
+            0 => return true,
+        };
+
+        let previous = self.focus;
+        self.switch(target, Reason::Wheel, changes);
+
+        // Same as with chords, the held keys are released where they were pressed.
+        self.previous = previous;
+        self.changed = true;
+
+        true
+    }
+
+    // Cycles through connected clients in order, wrapping around to the server.
+    fn next(&self) -> usize {
+        self.connected
+            .range(self.focus.target + 1..)
+            .next()
+            .copied()
+            .unwrap_or(0)
+    }
+
+    // The opposite of next.
+    fn prev(&self) -> usize {
+        let last = match self.focus.target {
+            0 => self.connected.last(),
+            target => self.connected.range(..target).next_back(),
+        };
+
+        last.copied().unwrap_or(0)
+    }
+
+    fn switch(&mut self, target: usize, reason: Reason, changes: &mut Vec<Change>) {
+        // Switching to a single target ends broadcasting.
+        if let Some(group) = self.focus.broadcast.take() {
+            changes.push(Change::BroadcastStopped { group });
+        }
+
+        if let Some(layout) = &mut self.layout {
+            layout.reset(target);
+        }
+
+        changes.push(Change::Switched {
+            previous: self.focus.target,
+            current: target,
+            reason,
+        });
+
+        self.focus.target = target;
+    }
+
+    // Relative motion is scaled for each of the targets with pointer settings, which get it as synthetic events instead.
+    fn scale(
+        &mut self,
+        id: usize,
+        event: Event,
+        targets: Vec<usize>,
+    ) -> (Vec<usize>, Vec<Synthetic>) {
+        let (axis, value) = match event {
+            Event::Rel(RelEvent { axis, value }) => (axis, value),
+            _ => return (targets, Vec::new()),
+        };
+
+        let mut synthetic = Vec::new();
+        let targets = targets
+            .into_iter()
+            .filter(|target| match self.pointers.get_mut(target) {
+                Some(pointer) => {
+                    if let Some(value) = pointer.rel(axis, value) {
+                        synthetic.push(Synthetic {
+                            target: *target,
+                            id,
+                            event: Event::Rel(RelEvent { axis, value }),
+                        });
+                    }
+
+                    false
+                }
+                None => true,
+            })
+            .collect();
+
+        (targets, synthetic)
+    }
+
+    fn targets(&self, focus: Focus) -> Vec<usize> {
+        let group = match focus.broadcast {
+            Some(group) => &self.broadcast_groups[group],
+            None => return vec![focus.target],
+        };
+
+        match &group.targets {
+            Some(targets) => targets
+                .iter()
+                .copied()
+                .filter(|target| self.connected.contains(target))
+                .collect(),
+            None => self.connected.iter().copied().collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rkvm_input::key::{Button, Keyboard};
+    use std::ffi::CStr;
+
+    const KEYBOARD: usize = 0;
+    const MOUSE: usize = 1;
+    const TABLET: usize = 2;
+
+    fn device(name: &CStr, vendor: u16, product: u16) -> DeviceInfo {
+        DeviceInfo {
+            name: name.into(),
+            vendor,
+            product,
+            ..Default::default()
+        }
+    }
+
+    fn router(extra: &str) -> Router {
+        let config = format!(
+            r#"
+            listen = "0.0.0.0:5258"
+            switch-keys = ["left-alt", "left-ctrl"]
+            certificate = "/etc/rkvm/certificate.pem"
+            key = "/etc/rkvm/key.pem"
+            password = "123456789"
+            {}
+
+            [[clients]]
+            addr = "10.10.0.1"
+            name = "first"
+            goto-keys = ["left-alt", "f2"]
+
+            [[clients]]
+            addr = "10.10.0.2"
+            name = "second"
+            goto-keys = ["left-alt", "f3"]
+
+            [[clients]]
+            addr = "10.10.0.3"
+            name = "third"
+            "#,
+            extra
+        );
+
+        let config = toml::from_str::<Config>(&config).unwrap();
+        let mut router = Router::new(&config).unwrap();
+        router.register_device(KEYBOARD, &device(c"Keyboard", 1, 1));
+        router.register_device(MOUSE, &device(c"Mouse", 1, 2));
+        router.register_device(TABLET, &device(c"Tablet", 2, 1));
+
+        router
+    }
+
+    fn key(key: Keyboard, down: bool) -> Event {
+        Event::Key(KeyEvent {
+            key: Key::Key(key),
+            down,
+        })
+    }
+
+    fn motion(axis: RelAxis, value: i32) -> Event {
+        Event::Rel(RelEvent { axis, value })
+    }
+
+    fn targets(router: &mut Router, event: Event) -> Vec<usize> {
+        router.route(KEYBOARD, event).targets
+    }
+
+    // Presses the keys in order and releases them in reverse order, returning the targets of each event.
+    fn chord(router: &mut Router, keys: &[Keyboard]) -> Vec<usize> {
+        let mut all = Vec::new();
+
+        for &k in keys {
+            all.extend(targets(router, key(k, true)));
+        }
+
+        for &k in keys.iter().rev() {
+            all.extend(targets(router, key(k, false)));
+        }
+
+        all
+    }
+
+    fn switch(router: &mut Router) {
+        chord(router, &[Keyboard::LeftAlt, Keyboard::LeftCtrl]);
+    }
+
+    #[test]
+    fn starts_on_server() {
+        let mut router = router("");
+        router.connect(1);
+
+        assert_eq!(router.current(), 0);
+        assert_eq!(targets(&mut router, key(Keyboard::A, true)), [0]);
+    }
+
+    #[test]
+    fn plain_events_are_not_changed() {
+        let mut router = router("");
+        router.connect(1);
+        switch(&mut router);
+
+        let route = router.route(MOUSE, motion(RelAxis::X, 5));
+        assert_eq!(route.targets, [1]);
+        assert_eq!(route.events, [motion(RelAxis::X, 5)]);
+        assert!(route.changes.is_empty());
+    }
+
+    #[test]
+    fn switch_keys_cycle() {
+        let mut router = router("");
+        router.connect(1);
+        router.connect(2);
+        router.connect(3);
+
+        for expected in [1, 2, 3, 0, 1] {
+            switch(&mut router);
+            assert_eq!(router.current(), expected);
+        }
+    }
+
+    #[test]
+    fn switch_keys_report_change() {
+        let mut router = router("");
+        router.connect(1);
+
+        router.route(KEYBOARD, key(Keyboard::LeftAlt, true));
+        let route = router.route(KEYBOARD, key(Keyboard::LeftCtrl, true));
+
+        assert_eq!(
+            route.changes,
+            [Change::Switched {
+                previous: 0,
+                current: 1,
+                reason: Reason::Keys,
+            }]
+        );
+    }
+
+    #[test]
+    fn cycling_skips_disconnected_static_clients() {
+        let mut router = router("");
+        router.connect(3);
+
+        switch(&mut router);
+        assert_eq!(router.current(), 3);
+
+        switch(&mut router);
+        assert_eq!(router.current(), 0);
+    }
+
+    #[test]
+    fn cycling_without_clients_stays_on_server() {
+        let mut router = router("");
+
+        router.route(KEYBOARD, key(Keyboard::LeftAlt, true));
+        let route = router.route(KEYBOARD, key(Keyboard::LeftCtrl, true));
+
+        assert_eq!(router.current(), 0);
+        assert_eq!(
+            route.changes,
+            [Change::Switched {
+                previous: 0,
+                current: 0,
+                reason: Reason::Keys,
+            }]
+        );
+    }
+
+    #[test]
+    fn cycling_includes_dynamic_clients() {
+        let mut router = router("");
+        router.connect(2);
+        router.connect(7);
+
+        switch(&mut router);
+        assert_eq!(router.current(), 2);
+
+        switch(&mut router);
+        assert_eq!(router.current(), 7);
+
+        switch(&mut router);
+        assert_eq!(router.current(), 0);
+    }
+
+    #[test]
+    fn goto_keys() {
+        let mut router = router(r#"goto-keys = ["left-alt", "f1"]"#);
+        router.connect(1);
+        router.connect(2);
+
+        chord(&mut router, &[Keyboard::LeftAlt, Keyboard::F3]);
+        assert_eq!(router.current(), 2);
+
+        chord(&mut router, &[Keyboard::LeftAlt, Keyboard::F2]);
+        assert_eq!(router.current(), 1);
+
+        chord(&mut router, &[Keyboard::LeftAlt, Keyboard::F1]);
+        assert_eq!(router.current(), 0);
+    }
+
+    #[test]
+    fn goto_keys_ignore_disconnected_clients() {
+        let mut router = router("");
+        router.connect(1);
+
+        chord(&mut router, &[Keyboard::LeftAlt, Keyboard::F3]);
+        assert_eq!(router.current(), 0);
+    }
+
+    #[test]
+    fn goto_keys_ignore_order() {
+        let mut router = router("");
+        router.connect(2);
+
+        chord(&mut router, &[Keyboard::F3, Keyboard::LeftAlt]);
+        assert_eq!(router.current(), 2);
+    }
+
+    #[test]
+    fn releases_go_to_previous_target() {
+        let mut router = router("");
+        router.connect(1);
+
+        let all = chord(&mut router, &[Keyboard::LeftAlt, Keyboard::LeftCtrl]);
+
+        // Both presses happened on the server, so do both releases.
+        assert_eq!(all, [0, 0, 0, 0]);
+        assert_eq!(router.current(), 1);
+        assert_eq!(targets(&mut router, key(Keyboard::A, true)), [1]);
+    }
+
+    #[test]
+    fn releases_go_to_previous_target_until_all_released() {
+        let mut router = router("");
+        router.connect(1);
+
+        targets(&mut router, key(Keyboard::LeftAlt, true));
+        targets(&mut router, key(Keyboard::LeftCtrl, true));
+
+        // Other keys already go to the new target.
+        assert_eq!(targets(&mut router, key(Keyboard::A, true)), [1]);
+
+        // Pressing the switch keys again doesn't switch again.
+        assert_eq!(targets(&mut router, key(Keyboard::LeftCtrl, false)), [0]);
+        assert_eq!(targets(&mut router, key(Keyboard::LeftCtrl, true)), [0]);
+        assert_eq!(router.current(), 1);
+
+        assert_eq!(targets(&mut router, key(Keyboard::LeftCtrl, false)), [0]);
+        assert_eq!(targets(&mut router, key(Keyboard::LeftAlt, false)), [0]);
+
+        // All released, now switch keys go to the new target.
+        assert_eq!(targets(&mut router, key(Keyboard::LeftAlt, true)), [1]);
+    }
+
+    #[test]
+    fn propagate_switch_keys_adds_sync() {
+        let mut router = router("");
+
+        let route = router.route(KEYBOARD, key(Keyboard::LeftAlt, true));
+        assert_eq!(route.targets, [0]);
+        assert_eq!(
+            route.events,
+            [key(Keyboard::LeftAlt, true), Event::Sync(SyncEvent::All)]
+        );
+    }
+
+    #[test]
+    fn propagate_switch_keys_disabled() {
+        let mut router = router("propagate-switch-keys = false");
+        router.connect(1);
+
+        let route = router.route(KEYBOARD, key(Keyboard::LeftAlt, true));
+        assert!(route.targets.is_empty());
+        assert!(route.events.is_empty());
+
+        let route = router.route(KEYBOARD, key(Keyboard::LeftCtrl, true));
+        assert!(route.events.is_empty());
+        assert_eq!(router.current(), 1);
+
+        // Other keys are still propagated.
+        let route = router.route(KEYBOARD, key(Keyboard::A, true));
+        assert_eq!(route.targets, [1]);
+        assert_eq!(route.events, [key(Keyboard::A, true)]);
+    }
+
+    // Returns the synthetic events written to the target as if they came from the device.
+    fn synthetic(route: &Route, target: usize, id: usize) -> Vec<Event> {
+        route
+            .synthetic
+            .iter()
+            .filter(|synthetic| synthetic.target == target && synthetic.id == id)
+            .map(|synthetic| synthetic.event)
+            .collect()
+    }
+
+    #[test]
+    fn held_keys_are_released_on_switch() {
+        let mut router = router("");
+        router.connect(1);
+
+        let button = Event::Key(KeyEvent {
+            key: Key::Button(Button::Left),
+            down: true,
+        });
+
+        router.route(KEYBOARD, key(Keyboard::LeftShift, true));
+        router.route(MOUSE, button);
+        router.route(KEYBOARD, key(Keyboard::LeftAlt, true));
+        let route = router.route(KEYBOARD, key(Keyboard::LeftCtrl, true));
+
+        let keyboard = synthetic(&route, 0, KEYBOARD);
+        assert_eq!(keyboard.len(), 4);
+        assert_eq!(keyboard.last(), Some(&Event::Sync(SyncEvent::All)));
+        for k in [Keyboard::LeftShift, Keyboard::LeftAlt, Keyboard::LeftCtrl] {
+            assert!(keyboard.contains(&key(k, false)));
+        }
+
+        let released = Event::Key(KeyEvent {
+            key: Key::Button(Button::Left),
+            down: false,
+        });
+        assert_eq!(
+            synthetic(&route, 0, MOUSE),
+            [released, Event::Sync(SyncEvent::All)]
+        );
+
+        // Nothing is pressed on the new target.
+        assert!(synthetic(&route, 1, KEYBOARD).is_empty());
+
+        // Physical releases don't synthesize anything.
+        for k in [Keyboard::LeftCtrl, Keyboard::LeftAlt, Keyboard::LeftShift] {
+            assert!(router.route(KEYBOARD, key(k, false)).synthetic.is_empty());
+        }
+    }
+
+    #[test]
+    fn repeats_follow_held_keys() {
+        let mut router = router("");
+        router.connect(1);
+
+        let repeat = Event::Repeat(Key::Key(Keyboard::A));
+
+        router.route(KEYBOARD, key(Keyboard::A, true));
+        assert_eq!(targets(&mut router, repeat), [0]);
+
+        // The key was released on the server when switching.
+        switch(&mut router);
+        assert!(targets(&mut router, repeat).is_empty());
+
+        router.route(KEYBOARD, key(Keyboard::A, true));
+        assert_eq!(targets(&mut router, repeat), [1]);
+
+        router.route(KEYBOARD, key(Keyboard::A, false));
+        assert!(targets(&mut router, repeat).is_empty());
+    }
+
+    #[test]
+    fn held_keys_are_released_on_screen_edge() {
+        let mut router = router(
+            r#"
+            [layout]
+            width = 1000
+            height = 1000
+            right = { client = "second", width = 1000, height = 1000 }
+            "#,
+        );
+        router.connect(2);
+
+        router.route(KEYBOARD, key(Keyboard::B, true));
+        let route = router.route(MOUSE, motion(RelAxis::X, 1000));
+
+        assert_eq!(
+            synthetic(&route, 0, KEYBOARD),
+            [key(Keyboard::B, false), Event::Sync(SyncEvent::All)]
+        );
+    }
+
+    #[test]
+    fn carry_modifiers() {
+        let mut router = router("carry-modifiers = true");
+        router.connect(1);
+
+        router.route(KEYBOARD, key(Keyboard::RightShift, true));
+        router.route(KEYBOARD, key(Keyboard::B, true));
+        router.route(KEYBOARD, key(Keyboard::LeftAlt, true));
+        let route = router.route(KEYBOARD, key(Keyboard::LeftCtrl, true));
+
+        assert_eq!(synthetic(&route, 0, KEYBOARD).len(), 5);
+
+        // Switch keys and other keys are not carried over.
+        assert_eq!(
+            synthetic(&route, 1, KEYBOARD),
+            [key(Keyboard::RightShift, true), Event::Sync(SyncEvent::All)]
+        );
+
+        assert_eq!(targets(&mut router, key(Keyboard::RightShift, false)), [1]);
+    }
+
+    #[test]
+    fn control() {
+        let mut router = router("");
+        router.connect(2);
+
+        assert_eq!(router.goto(1), None);
+
+        router.route(KEYBOARD, key(Keyboard::A, true));
+        let route = router.goto(2).unwrap();
+        assert_eq!(router.current(), 2);
+        assert_eq!(
+            route.changes,
+            [Change::Switched {
+                previous: 0,
+                current: 2,
+                reason: Reason::Control,
+            }]
+        );
+        assert_eq!(
+            synthetic(&route, 0, KEYBOARD),
+            [key(Keyboard::A, false), Event::Sync(SyncEvent::All)]
+        );
+
+        router.cycle();
+        assert_eq!(router.current(), 0);
+    }
+
+    #[test]
+    fn disconnecting_current_returns_to_server() {
+        let mut router = router("");
+        router.connect(1);
+        switch(&mut router);
+
+        assert_eq!(
+            router.disconnect(1),
+            [Change::Switched {
+                previous: 1,
+                current: 0,
+                reason: Reason::Disconnect,
+            }]
+        );
+        assert_eq!(router.current(), 0);
+        assert_eq!(targets(&mut router, key(Keyboard::A, true)), [0]);
+    }
+
+    #[test]
+    fn disconnecting_current_stops_broadcasting() {
+        let mut router = router(
+            r#"
+            [[broadcast-groups]]
+            keys = ["left-alt", "f12"]
+            "#,
+        );
+        router.connect(1);
+        router.connect(2);
+        switch(&mut router);
+        chord(&mut router, &[Keyboard::LeftAlt, Keyboard::F12]);
+        assert_eq!(targets(&mut router, key(Keyboard::A, true)), [1, 2]);
+
+        assert_eq!(
+            router.disconnect(1),
+            [
+                Change::BroadcastStopped { group: 0 },
+                Change::Switched {
+                    previous: 1,
+                    current: 0,
+                    reason: Reason::Disconnect,
+                }
+            ]
+        );
+        assert_eq!(targets(&mut router, key(Keyboard::B, true)), [0]);
+    }
+
+    #[test]
+    fn disconnecting_previous_sends_pending_releases_to_server() {
+        let mut router = router("");
+        router.connect(1);
+        router.connect(2);
+        switch(&mut router);
+
+        // Switch to the second client, keeping the switch keys pressed.
+        targets(&mut router, key(Keyboard::LeftAlt, true));
+        targets(&mut router, key(Keyboard::LeftCtrl, true));
+        assert_eq!(router.current(), 2);
+
+        assert!(router.disconnect(1).is_empty());
+
+        // The index might be reused by another client in the meantime.
+        router.connect(1);
+        assert_eq!(targets(&mut router, key(Keyboard::LeftCtrl, false)), [0]);
+        assert_eq!(targets(&mut router, key(Keyboard::LeftAlt, false)), [0]);
+        assert_eq!(targets(&mut router, key(Keyboard::A, true)), [2]);
+    }
+
+    #[test]
+    fn disconnecting_other_keeps_current() {
+        let mut router = router("");
+        router.connect(1);
+        router.connect(2);
+        switch(&mut router);
+
+        assert!(router.disconnect(2).is_empty());
+        assert_eq!(router.current(), 1);
+    }
+
+    #[test]
+    fn buttons_can_be_switch_keys() {
+        let mut router = router(r#"goto-keys = ["b-left", "b-right"]"#);
+        router.connect(1);
+        switch(&mut router);
+
+        let button = |button, down| {
+            Event::Key(KeyEvent {
+                key: Key::Button(button),
+                down,
+            })
+        };
+
+        router.route(MOUSE, button(Button::Left, true));
+        router.route(MOUSE, button(Button::Right, true));
+        assert_eq!(router.current(), 0);
+    }
+
+    #[test]
+    fn wheel_switch_keys() {
+        let mut router = router(r#"wheel-switch-keys = ["side"]"#);
+        router.connect(1);
+        router.connect(2);
+
+        let side = |down| {
+            Event::Key(KeyEvent {
+                key: Key::Button(Button::Side),
+                down,
+            })
+        };
+
+        // Scrolling works as usual without the button.
+        assert_eq!(router.route(MOUSE, motion(RelAxis::Wheel, -1)).targets, [0]);
+
+        router.route(MOUSE, side(true));
+
+        let route = router.route(MOUSE, motion(RelAxis::Wheel, -1));
+        assert!(route.events.is_empty());
+        assert_eq!(
+            route.changes,
+            [Change::Switched {
+                previous: 0,
+                current: 1,
+                reason: Reason::Wheel,
+            }]
+        );
+
+        assert!(router
+            .route(MOUSE, motion(RelAxis::WheelHiRes, -120))
+            .events
+            .is_empty());
+
+        router.route(MOUSE, motion(RelAxis::Wheel, -1));
+        assert_eq!(router.current(), 2);
+
+        // Scrolling up goes back, wrapping around through the server.
+        for expected in [1, 0, 2] {
+            router.route(MOUSE, motion(RelAxis::Wheel, 1));
+            assert_eq!(router.current(), expected);
+        }
+
+        router.route(MOUSE, side(false));
+        assert_eq!(router.route(MOUSE, motion(RelAxis::Wheel, 1)).targets, [2]);
+    }
+
+    #[test]
+    fn buttons_held_across_wheel_switch() {
+        let mut router = router(r#"wheel-switch-keys = ["side"]"#);
+        router.connect(1);
+
+        let button = |button, down| {
+            Event::Key(KeyEvent {
+                key: Key::Button(button),
+                down,
+            })
+        };
+
+        router.route(MOUSE, button(Button::Left, true));
+        router.route(MOUSE, button(Button::Side, true));
+
+        let route = router.route(MOUSE, motion(RelAxis::Wheel, -1));
+        assert_eq!(router.current(), 1);
+        assert!(synthetic(&route, 0, MOUSE).contains(&button(Button::Left, false)));
+
+        // The switch key is released on the server, where it was pressed.
+        assert_eq!(
+            router.route(MOUSE, button(Button::Side, false)).targets,
+            [0]
+        );
+        assert!(router.held.get(&0).is_none_or(HashSet::is_empty));
+
+        // Afterwards, events go to the new target.
+        assert_eq!(
+            router.route(MOUSE, button(Button::Left, false)).targets,
+            [1]
+        );
+        assert_eq!(router.route(MOUSE, button(Button::Side, true)).targets, [1]);
+    }
+
+    #[test]
+    fn pinned_devices() {
+        let mut router = router(
+            r#"
+            [[device-routes]]
+            client = "second"
+            vendor-id = 2
+            "#,
+        );
+
+        assert_eq!(router.pinned(TABLET), Some(2));
+        assert_eq!(router.pinned(MOUSE), None);
+
+        // Stays on the server while the client is not connected.
+        assert_eq!(router.route(TABLET, motion(RelAxis::X, 1)).targets, [0]);
+
+        router.connect(1);
+        router.connect(2);
+        assert_eq!(router.route(TABLET, motion(RelAxis::X, 1)).targets, [2]);
+
+        switch(&mut router);
+        assert_eq!(router.route(TABLET, motion(RelAxis::X, 1)).targets, [2]);
+        assert_eq!(router.route(MOUSE, motion(RelAxis::X, 1)).targets, [1]);
+    }
+
+    #[test]
+    fn pinned_devices_do_not_switch() {
+        let mut router = router(
+            r#"
+            [[device-routes]]
+            client = "second"
+            name = "Keyboard"
+            "#,
+        );
+        router.connect(1);
+
+        assert_eq!(
+            chord(&mut router, &[Keyboard::LeftAlt, Keyboard::LeftCtrl]),
+            [0; 4]
+        );
+        assert_eq!(router.current(), 0);
+    }
+
+    #[test]
+    fn unregistered_devices_are_not_pinned() {
+        let mut router = router(
+            r#"
+            [[device-routes]]
+            client = "second"
+            vendor-id = 2
+            "#,
+        );
+
+        router.unregister_device(TABLET);
+        assert_eq!(router.pinned(TABLET), None);
+    }
+
+    #[test]
+    fn unknown_client() {
+        let config = toml::from_str::<Config>(
+            r#"
+            listen = "0.0.0.0:5258"
+            switch-keys = ["left-alt", "left-ctrl"]
+            certificate = "/etc/rkvm/certificate.pem"
+            key = "/etc/rkvm/key.pem"
+            password = "123456789"
+
+            [[device-routes]]
+            client = "nobody"
+            vendor-id = 2
+            "#,
+        )
+        .unwrap();
+
+        assert!(matches!(
+            Router::new(&config),
+            Err(Error::UnknownClient(client)) if client == "nobody"
+        ));
+    }
+
+    #[test]
+    fn broadcast_to_all() {
+        let mut router = router(
+            r#"
+            [[broadcast-groups]]
+            keys = ["left-alt", "f12"]
+            "#,
+        );
+        router.connect(1);
+        router.connect(3);
+
+        chord(&mut router, &[Keyboard::LeftAlt, Keyboard::F12]);
+        assert_eq!(targets(&mut router, key(Keyboard::A, true)), [1, 3]);
+
+        // Toggles back to the focused target.
+        chord(&mut router, &[Keyboard::LeftAlt, Keyboard::F12]);
+        assert_eq!(targets(&mut router, key(Keyboard::A, true)), [0]);
+    }
+
+    #[test]
+    fn broadcast_to_group() {
+        let mut router = router(
+            r#"
+            [[broadcast-groups]]
+            keys = ["left-alt", "f12"]
+            clients = ["first", "second"]
+            "#,
+        );
+        router.connect(1);
+        router.connect(3);
+
+        router.route(KEYBOARD, key(Keyboard::LeftAlt, true));
+        let route = router.route(KEYBOARD, key(Keyboard::F12, true));
+        assert_eq!(route.changes, [Change::BroadcastStarted { group: 0 }]);
+
+        router.route(KEYBOARD, key(Keyboard::F12, false));
+        router.route(KEYBOARD, key(Keyboard::LeftAlt, false));
+
+        // Disconnected members are skipped.
+        assert_eq!(targets(&mut router, key(Keyboard::A, true)), [1]);
+
+        router.connect(2);
+        assert_eq!(targets(&mut router, key(Keyboard::A, true)), [1, 2]);
+    }
+
+    #[test]
+    fn switching_ends_broadcast() {
+        let mut router = router(
+            r#"
+            [[broadcast-groups]]
+            keys = ["left-alt", "f12"]
+            "#,
+        );
+        router.connect(1);
+        router.connect(2);
+
+        chord(&mut router, &[Keyboard::LeftAlt, Keyboard::F12]);
+
+        router.route(KEYBOARD, key(Keyboard::LeftAlt, true));
+        let route = router.route(KEYBOARD, key(Keyboard::F3, true));
+        assert_eq!(
+            route.changes,
+            [
+                Change::BroadcastStopped { group: 0 },
+                Change::Switched {
+                    previous: 0,
+                    current: 2,
+                    reason: Reason::Keys,
+                },
+            ]
+        );
+
+        // The chord was pressed while broadcasting, so it's released there as well.
+        assert_eq!(targets(&mut router, key(Keyboard::F3, false)), [1, 2]);
+        assert_eq!(targets(&mut router, key(Keyboard::LeftAlt, false)), [1, 2]);
+        assert_eq!(targets(&mut router, key(Keyboard::A, true)), [2]);
+    }
+
+    #[test]
+    fn macro_runs_after_release() {
+        let mut router = router(
+            r#"
+            [[macros]]
+            keys = ["left-alt", "f9"]
+            steps = [{ press = "a" }, { release = "a" }]
+            "#,
+        );
+        router.connect(1);
+        switch(&mut router);
+
+        router.route(KEYBOARD, key(Keyboard::LeftAlt, true));
+        assert!(router
+            .route(KEYBOARD, key(Keyboard::F9, true))
+            .changes
+            .is_empty());
+        assert!(router
+            .route(KEYBOARD, key(Keyboard::F9, false))
+            .changes
+            .is_empty());
+
+        let route = router.route(KEYBOARD, key(Keyboard::LeftAlt, false));
+        assert_eq!(
+            route.changes,
+            [Change::Macro {
+                idx: 0,
+                id: KEYBOARD
+            }]
+        );
+        assert_eq!(router.current(), 1);
+    }
+
+    #[test]
+    fn injected_events_never_switch() {
+        let mut router = router("");
+        router.connect(1);
+        switch(&mut router);
+
+        for k in [Keyboard::LeftAlt, Keyboard::LeftCtrl] {
+            let route = router.inject(KEYBOARD, key(k, true));
+            assert_eq!(route.targets, [1]);
+            assert_eq!(route.events, [key(k, true)]);
+        }
+
+        assert_eq!(router.current(), 1);
+
+        // Injected keys are released like any other when switching.
+        switch(&mut router);
+        assert_eq!(router.current(), 0);
+        assert!(router.held.values().all(HashSet::is_empty));
+
+        router.unregister_device(KEYBOARD);
+        assert!(router
+            .inject(KEYBOARD, key(Keyboard::A, true))
+            .targets
+            .is_empty());
+    }
+
+    #[test]
+    fn idle_returns_to_server() {
+        let mut router = router("idle-timeout = 60");
+        router.connect(1);
+        assert_eq!(router.idle_deadline(), None);
+
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+
+        router.route_at(KEYBOARD, key(Keyboard::LeftAlt, true), at(0));
+        router.route_at(KEYBOARD, key(Keyboard::LeftCtrl, true), at(0));
+        assert_eq!(router.current(), 1);
+        assert_eq!(router.idle_deadline(), Some(at(60)));
+
+        router.route_at(MOUSE, motion(RelAxis::X, 5), at(30));
+        assert_eq!(router.idle_deadline(), Some(at(90)));
+
+        assert_eq!(
+            router.idle().changes,
+            [Change::Switched {
+                previous: 1,
+                current: 0,
+                reason: Reason::Idle,
+            }]
+        );
+        assert_eq!(router.idle_deadline(), None);
+    }
+
+    #[test]
+    fn idle_goes_to_fallback() {
+        let mut router = router(
+            r#"
+            idle-timeout = 60
+            idle-fallback = "first"
+            "#,
+        );
+        router.connect(1);
+        router.connect(2);
+
+        router.goto(2).unwrap();
+        router.idle();
+        assert_eq!(router.current(), 1);
+        assert_eq!(router.idle_deadline(), None);
+
+        // Without the fallback client, the server is used.
+        router.disconnect(1);
+        router.goto(2).unwrap();
+        router.idle();
+        assert_eq!(router.current(), 0);
+    }
+
+    // Taps the key at the given offsets in milliseconds, each tap lasting 10ms.
+    fn taps(router: &mut Router, k: Keyboard, offsets: &[u64]) -> Vec<Change> {
+        let start = Instant::now();
+        let mut changes = Vec::new();
+
+        for &offset in offsets {
+            let at = |millis| start + Duration::from_millis(millis);
+
+            for (down, millis) in [(true, offset), (false, offset + 10)] {
+                let route = router.route_at(KEYBOARD, key(k, down), at(millis));
+
+                // Taps are never swallowed.
+                assert_eq!(route.events, [key(k, down)]);
+                changes.extend(route.changes);
+            }
+        }
+
+        changes
+    }
+
+    const TAP_TRIGGERS: &str = r#"
+        [[tap-triggers]]
+        key = "scroll-lock"
+        count = 2
+
+        [[tap-triggers]]
+        key = "right-ctrl"
+        count = 3
+        window = 1000
+        client = "third"
+    "#;
+
+    #[test]
+    fn double_tap_cycles() {
+        let mut router = router(TAP_TRIGGERS);
+        router.connect(2);
+
+        assert_eq!(
+            taps(&mut router, Keyboard::ScrollLock, &[0, 100]),
+            [Change::Switched {
+                previous: 0,
+                current: 2,
+                reason: Reason::Taps,
+            }]
+        );
+
+        taps(&mut router, Keyboard::ScrollLock, &[0, 100]);
+        assert_eq!(router.current(), 0);
+    }
+
+    #[test]
+    fn tap_count_goes_to_client() {
+        let mut router = router(TAP_TRIGGERS);
+        router.connect(1);
+        router.connect(3);
+
+        taps(&mut router, Keyboard::RightCtrl, &[0, 200]);
+        assert_eq!(router.current(), 0);
+
+        taps(&mut router, Keyboard::RightCtrl, &[0, 200, 400]);
+        assert_eq!(router.current(), 3);
+    }
+
+    #[test]
+    fn slow_taps_do_not_switch() {
+        let mut router = router(TAP_TRIGGERS);
+        router.connect(1);
+
+        assert!(taps(&mut router, Keyboard::ScrollLock, &[0, 300]).is_empty());
+        assert_eq!(router.current(), 0);
+    }
+
+    #[test]
+    fn other_keys_interrupt_taps() {
+        let mut router = router(TAP_TRIGGERS);
+        router.connect(1);
+
+        taps(&mut router, Keyboard::ScrollLock, &[0]);
+        taps(&mut router, Keyboard::A, &[20]);
+        assert!(taps(&mut router, Keyboard::ScrollLock, &[40]).is_empty());
+        assert_eq!(router.current(), 0);
+    }
+
+    #[test]
+    fn screen_edge() {
+        let mut router = router(
+            r#"
+            [layout]
+            width = 1000
+            height = 1000
+            right = { client = "second", width = 1000, height = 1000 }
+            "#,
+        );
+
+        // Nothing happens while the neighbour is not connected.
+        let route = router.route(MOUSE, motion(RelAxis::X, 1000));
+        assert_eq!(route.targets, [0]);
+        assert!(route.changes.is_empty());
+
+        router.connect(2);
+
+        let route = router.route(MOUSE, motion(RelAxis::X, 1));
+        assert_eq!(route.targets, [2]);
+        assert_eq!(route.events, [motion(RelAxis::X, -1000)]);
+        assert_eq!(
+            route.changes,
+            [Change::Switched {
+                previous: 0,
+                current: 2,
+                reason: Reason::Edge,
+            }]
+        );
+
+        let route = router.route(MOUSE, motion(RelAxis::X, -1));
+        assert_eq!(route.targets, [0]);
+        assert_eq!(router.current(), 0);
+    }
+
+    #[test]
+    fn screen_edge_ignored_while_broadcasting() {
+        let mut router = router(
+            r#"
+            [[broadcast-groups]]
+            keys = ["left-alt", "f12"]
+
+            [layout]
+            width = 1000
+            height = 1000
+            right = { client = "second", width = 1000, height = 1000 }
+            "#,
+        );
+        router.connect(2);
+
+        chord(&mut router, &[Keyboard::LeftAlt, Keyboard::F12]);
+
+        let route = router.route(MOUSE, motion(RelAxis::X, 1000));
+        assert_eq!(route.targets, [2]);
+        assert!(route.changes.is_empty());
+        assert_eq!(router.current(), 0);
+    }
+
+    #[test]
+    fn screen_edge_with_sensitivity() {
+        let config = toml::from_str::<Config>(
+            r#"
+            listen = "0.0.0.0:5258"
+            switch-keys = ["left-alt", "left-ctrl"]
+            certificate = "/etc/rkvm/certificate.pem"
+            key = "/etc/rkvm/key.pem"
+            password = "123456789"
+
+            [layout]
+            width = 1000
+            height = 1000
+            right = { client = "laptop", width = 1000, height = 1000 }
+
+            [[clients]]
+            name = "laptop"
+            pointer = { sensitivity = 0.5 }
+            "#,
+        )
+        .unwrap();
+
+        let mut router = Router::new(&config).unwrap();
+        router.register_device(MOUSE, &device(c"Mouse", 1, 2));
+        router.route(MOUSE, motion(RelAxis::X, 1000));
+        router.connect(1);
+
+        // The push placing the cursor at the edge is not scaled.
+        let route = router.route(MOUSE, motion(RelAxis::X, 1));
+        assert_eq!(route.targets, [1]);
+        assert_eq!(route.events, [motion(RelAxis::X, -1000)]);
+
+        let route = router.route(MOUSE, motion(RelAxis::X, 1000));
+        assert_eq!(route.events, [motion(RelAxis::X, 500)]);
+
+        // Unscaled, this would have crossed the edge back to the server.
+        let route = router.route(MOUSE, motion(RelAxis::X, -1001));
+        assert_eq!(route.events, [motion(RelAxis::X, -500)]);
+        assert!(route.changes.is_empty());
+
+        let route = router.route(MOUSE, motion(RelAxis::X, -2));
+        assert_eq!(route.targets, [0]);
+        assert_eq!(router.current(), 0);
+    }
+
+    #[test]
+    fn broadcast_motion_is_scaled_per_client() {
+        let config = toml::from_str::<Config>(
+            r#"
+            listen = "0.0.0.0:5258"
+            switch-keys = ["left-alt", "left-ctrl"]
+            certificate = "/etc/rkvm/certificate.pem"
+            key = "/etc/rkvm/key.pem"
+            password = "123456789"
+
+            [[broadcast-groups]]
+            keys = ["left-alt", "f12"]
+
+            [[clients]]
+            name = "laptop"
+            pointer = { sensitivity = 2.0 }
+
+            [[clients]]
+            name = "desktop"
+            "#,
+        )
+        .unwrap();
+
+        let mut router = Router::new(&config).unwrap();
+        router.register_device(KEYBOARD, &device(c"Keyboard", 1, 1));
+        router.register_device(MOUSE, &device(c"Mouse", 1, 2));
+        router.connect(1);
+        router.connect(2);
+
+        chord(&mut router, &[Keyboard::LeftAlt, Keyboard::F12]);
+
+        let route = router.route(MOUSE, motion(RelAxis::X, 3));
+        assert_eq!(route.targets, [2]);
+        assert_eq!(route.events, [motion(RelAxis::X, 3)]);
+        assert_eq!(
+            route.synthetic,
+            [Synthetic {
+                target: 1,
+                id: MOUSE,
+                event: motion(RelAxis::X, 6),
+            }]
+        );
+    }
+}
@@ -1,33 +1,45 @@
 use rkvm_input::abs::{AbsAxis, AbsInfo};
-use rkvm_input::device::DeviceSpec;
+use rkvm_input::device::DeviceInfo;
 use rkvm_input::event::Event;
-use rkvm_input::key::{Key, KeyEvent};
-use rkvm_input::monitor::{Monitor,MonitorPlatform};
+use rkvm_input::ff::{Ff, FfEffect, FfEvent};
 use rkvm_input::interceptor::InterceptorPlatform;
+use rkvm_input::key::Key;
+use rkvm_input::led::{Led, LedEvent};
+use rkvm_input::monitor::{Monitor, MonitorPlatform};
 use rkvm_input::rel::RelAxis;
-use rkvm_input::sync::SyncEvent;
 use rkvm_net::auth::{AuthChallenge, AuthResponse, AuthStatus};
+use rkvm_net::control::{ClientStatus, DeviceStatus, Request, Response, Status, Target};
 use rkvm_net::message::Message;
 use rkvm_net::version::Version;
-use rkvm_net::{Pong, Update};
+use rkvm_net::{Feedback, Hello, Update};
 use slab::Slab;
 use std::collections::{HashMap, HashSet, VecDeque};
-use std::ffi::CString;
+use std::future;
 use std::io::{self, ErrorKind};
-use std::net::{SocketAddr, IpAddr, Ipv4Addr};
+use std::net::SocketAddr;
+use std::sync::Arc;
 use std::time::Instant;
 use thiserror::Error;
 use tokio::io::{AsyncWriteExt, BufStream};
 use tokio::net::{TcpListener, TcpStream};
 use tokio::sync::mpsc::error::TrySendError;
 use tokio::sync::mpsc::{self, Receiver, Sender};
+use tokio::sync::{oneshot, watch};
 use tokio::time;
 use tokio_rustls::TlsAcceptor;
-use tracing::Instrument;
+use tracing::field;
+use tracing::{Instrument, Span};
 
-use crate::config::ClientConfig;
+use crate::config::{self, ClientConfig, Config};
+use crate::control;
+use crate::credentials::Credentials;
+use crate::hooks::{self, Hooks};
+use crate::macros::Macro;
+use crate::router::{Change, Reason, Route, Router};
+use crate::transform::Transform;
 
-const ADDR_UNKNOWN: SocketAddr = SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED),0);
+// Indexed by client index, entries of clients from the config are kept while they're disconnected.
+type Clients = Slab<Option<(Sender<Update>, SocketAddr, String)>>;
 
 #[derive(Error, Debug)]
 pub enum Error {
@@ -37,177 +49,261 @@ pub enum Error {
     Input(io::Error),
     #[error("Event queue overflow")]
     Overflow,
+    #[error("Control socket error: {0}")]
+    Control(io::Error),
 }
 
 pub async fn run(
-    listen: SocketAddr,
+    config: &Config,
     acceptor: TlsAcceptor,
-    password: &str,
-    switch_keys: &HashSet<Key>,
-    propagate_switch_keys: bool,
-    server_goto_keys: &Option<Vec<Key>>,
-    clients_config: &Vec<ClientConfig>,
-    device_allowlist: Vec<DeviceSpec>,
+    mut router: Router,
+    credentials: watch::Receiver<Arc<Credentials>>,
 ) -> Result<(), Error> {
-    let listener = TcpListener::bind(&listen).await.map_err(Error::Network)?;
-    tracing::info!("Listening on {}", listen);
+    let clients_config = &config.clients;
 
-    let mut monitor = Monitor::new(device_allowlist);
+    let listener = TcpListener::bind(&config.listen)
+        .await
+        .map_err(Error::Network)?;
+    tracing::info!("Listening on {}", config.listen);
+
+    let control_listener = match &config.control_socket {
+        Some(path) => {
+            let listener = control::listen(path).map_err(Error::Control)?;
+            tracing::info!("Listening for control commands on {}", path.display());
+
+            Some(listener)
+        }
+        None => None,
+    };
+
+    let mut monitor = Monitor::new(
+        config.device_allowlist.clone(),
+        config.device_denylist.clone(),
+        config.forward_switch_devices.unwrap_or(false),
+    );
+    let server_repeat = config.server_repeat.unwrap_or(false);
     let mut devices = Slab::<Device>::new();
-    let mut clients = Slab::<Option<(Sender<_>, SocketAddr)>>::new();
-    let mut current = 0;
-    let mut previous = 0;
-    let mut changed = false;
-    let mut pressed_keys = HashSet::new();
-    let mut all_switch_keys = switch_keys.clone();
-    let mut static_client = Vec::new();
-    let mut goto_keys: HashMap<Vec<Key>,usize> = HashMap::new();
-
-
-    if let Some(keys) = server_goto_keys {
-         goto_keys.insert(keys.clone(), 0);
-         all_switch_keys.extend(keys);
-    }
+    let mut next_serial = 0;
+    let mut clients = Clients::new();
 
-    for c in clients_config {
+    for _ in clients_config {
         clients.insert(None);
-        static_client.push(c.addr);
-        if let Some(k) = &c.goto_keys {
-            let keys:Vec<Key> = k.clone().into_iter().map(Into::into).collect();
-            goto_keys.insert(keys.clone(), static_client.len());
-            all_switch_keys.extend(keys);
-        }
     }
 
+    // Only clients present in the config have transforms, the rest get events unchanged.
+    let mut transforms = clients_config
+        .iter()
+        .map(Transform::new)
+        .collect::<Vec<_>>();
+    let identity = Transform::default();
+
     let (events_sender, mut events_receiver) = mpsc::channel(1);
+    let (macro_sender, mut macro_receiver) = mpsc::channel(1);
+    let macros = config
+        .macros
+        .iter()
+        .map(|r#macro| Arc::new(Macro::new(r#macro)))
+        .collect::<Vec<_>>();
+    let (control_sender, mut control_receiver) = mpsc::channel(1);
+    let (handshake_sender, mut handshake_receiver) = mpsc::channel(1);
+    let (closed_sender, mut closed_receiver) = mpsc::channel(1);
+    let (feedback_sender, mut feedback_receiver) = mpsc::channel(1);
+    let hooks = Hooks::new(config);
+
+    // LED state reported by clients, by target and device ID.
+    let mut leds = HashMap::new();
 
     loop {
         let event = async { events_receiver.recv().await.unwrap() };
+        // We hold a sender ourselves, so the channel never closes.
+        let macro_event = async { macro_receiver.recv().await.unwrap() };
+        let control_command = async { control_receiver.recv().await.unwrap() };
+        let handshake = async { handshake_receiver.recv().await.unwrap() };
+        let closed = async { closed_receiver.recv().await.unwrap() };
+        let feedback = async { feedback_receiver.recv().await.unwrap() };
+        let idle_deadline = router.idle_deadline();
+        let idle = async move {
+            match idle_deadline {
+                Some(deadline) => time::sleep_until(deadline.into()).await,
+                None => future::pending().await,
+            }
+        };
+        let control_accept = async {
+            match &control_listener {
+                Some(listener) => listener.accept().await,
+                None => future::pending().await,
+            }
+        };
 
-        tokio::select! {
+        let (id, route) = tokio::select! {
             result = listener.accept() => {
                 let (stream, addr) = result.map_err(Error::Network)?;
                 let acceptor = acceptor.clone();
-                let password = password.to_owned();
+                let credentials = credentials.clone();
+                let handshake_sender = handshake_sender.clone();
+                let closed_sender = closed_sender.clone();
+                let feedback_sender = feedback_sender.clone();
 
-                // Remove dead clients.
-                for id in 0..static_client.len() {
-                    if let Some((client, _)) = &clients[id] {
-                        if client.is_closed() {
-                            clients[id] = None
-                        }
-                    }
-                }
-                clients.retain(|idx, e|
-                    if idx < static_client.len() {
-                        true
-                    } else {
-                        match e {
-                            Some((client, _)) => !client.is_closed(),
-                            None => true,
+                let span = tracing::info_span!("connection", addr = %addr, name = field::Empty, idx = field::Empty);
+                tokio::spawn(
+                    async move {
+                        tracing::info!("Connected");
+
+                        match client(stream, addr, acceptor, credentials, handshake_sender, feedback_sender).await {
+                            Ok(()) => tracing::info!("Disconnected"),
+                            Err(err) => tracing::error!("Disconnected: {}", err),
                         }
+
+                        // Let the main task remove the client right away rather than on the next write.
+                        let _ = closed_sender.send(()).await;
                     }
+                    .instrument(span),
                 );
-                if !clients.contains(current) || clients[current].is_none()  {
-                    current = 0;
-                }
 
-                let init_updates = devices
+                continue;
+            }
+            Handshake { name, addr, reply } = handshake => {
+                remove_closed(&mut clients, clients_config.len(), &mut router, &hooks, &devices, &mut leds).await;
+
+                let (sender, receiver) = mpsc::channel(1);
+
+                let idx = assign(&mut clients, &mut transforms, clients_config, sender, addr, name);
+
+                router.connect(idx + 1);
+                hooks.connect(target(&clients, idx + 1));
+
+                let mut init_updates = devices
                     .iter()
+                    .filter(|(id, _)| created(&router, *id, idx + 1))
                     .map(|(id, device)| Update::CreateDevice {
                         id,
-                        name: device.name.clone(),
+                        name: device.info.name.clone(),
                         version: device.version,
-                        vendor: device.vendor,
-                        product: device.product,
+                        vendor: device.info.vendor,
+                        product: device.info.product,
                         rel: device.rel.clone(),
-                        abs: device.abs.clone(),
-                        keys: device.keys.clone(),
+                        abs: match transforms.get_mut(idx) {
+                            Some(transform) => transform.abs(id, &device.info, &device.abs),
+                            None => device.abs.clone(),
+                        },
+                        keys: transforms.get(idx).unwrap_or(&identity).keys(&device.keys),
+                        leds: device.leds.clone(),
+                        ff: device.ff.clone(),
+                        raw: device.raw.clone(),
                         delay: device.delay,
                         period: device.period,
+                        server_repeat,
                     })
-                    .collect();
+                    .collect::<VecDeque<_>>();
 
-                let (sender, receiver) = mpsc::channel(1);
+                init_updates.push_front(Update::Focus { active: router.current() == idx + 1 });
 
-                let index = static_client.iter().position(|ip| *ip == addr.ip());
-                let idx = match index {
-                    Some(idx) => {
-                        if clients[idx].is_some() {
-                            tracing::warn!("client {} already connected", addr);
-                            clients.insert(Some((sender, addr)))
-                        } else {
-                            clients[idx] = Some((sender, addr));
-                            idx
-                        }
-                    },
-                    None => clients.insert(Some((sender, addr))),
-                };
-                
-                let span = tracing::info_span!("connection", addr = %addr, idx = %idx);
-                tokio::spawn(
-                    async move {
-                        tracing::info!("Connected");
+                // If the connection is gone already, the client will be removed later on.
+                let _ = reply.send((idx, init_updates, receiver));
 
-                        match client(init_updates, receiver, stream, acceptor, &password).await {
-                            Ok(()) => tracing::info!("Disconnected"),
-                            Err(err) => tracing::error!("Disconnected: {}", err),
+                continue;
+            }
+            () = closed => {
+                remove_closed(&mut clients, clients_config.len(), &mut router, &hooks, &devices, &mut leds).await;
+                continue;
+            }
+            (idx, feedback) = feedback => {
+                match feedback {
+                    Feedback::Pong => {}
+                    Feedback::Led { id, event } => {
+                        let state = leds.entry((idx, id)).or_insert_with(HashMap::new);
+                        state.insert(event.led, event.on);
+
+                        if router.current() == idx {
+                            if let Some(device) = devices.get(id) {
+                                // Same as with events, don't block on the device task.
+                                let _ = device.commands.try_send(Command::ShowLeds(state.clone()));
+                            }
                         }
                     }
-                    .instrument(span),
-                );
+                    Feedback::Ff { id, event } => {
+                        // Only the current client and the one the device is pinned to get to use force feedback,
+                        // others could use up the effect slots of the device while in the background.
+                        let allowed = router.current() == idx || router.pinned(id) == Some(idx);
+
+                        if let Some(device) = devices.get(id).filter(|_| allowed) {
+                            if let Err(TrySendError::Full(_)) = device.commands.try_send(Command::Ff { target: idx, event }) {
+                                tracing::warn!(id = %id, "Dropped a force feedback request");
+                            }
+                        }
+                    }
+                }
+
+                continue;
             }
             result = monitor.read() => {
                 let mut interceptor = result.map_err(Error::Input)?;
 
-                let name = interceptor.name().to_owned();
+                let info = interceptor.info().clone();
                 let id = devices.vacant_key();
                 let version = interceptor.version();
-                let vendor = interceptor.vendor();
-                let product = interceptor.product();
                 let rel = interceptor.rel();
                 let abs = interceptor.abs();
                 let keys = interceptor.key();
                 let repeat = interceptor.repeat();
+                let device_leds = interceptor.leds().into_keys().collect::<HashSet<_>>();
+                let ff = interceptor.ff();
+                let raw = interceptor.raw();
+                let pinned = router.register_device(id, &info);
 
-                for (_, e) in &clients {
-                    match e {
-                        Some((sender, _)) => {
-                            let update = Update::CreateDevice {
-                                id,
-                                name: name.clone(),
-                                version: version.clone(),
-                                vendor: vendor.clone(),
-                                product: product.clone(),
-                                rel: rel.clone(),
-                                abs: abs.clone(),
-                                keys: keys.clone(),
-                                delay: repeat.delay,
-                                period: repeat.period,
-                            };
-
-                            let _ = sender.send(update).await;
-                        },
-                        None => {}
+                for (client_id, e) in &clients {
+                    if !created(&router, id, client_id + 1) {
+                        continue;
+                    }
+
+                    if let Some((sender, _, _)) = e {
+                        let update = Update::CreateDevice {
+                            id,
+                            name: info.name.clone(),
+                            version,
+                            vendor: info.vendor,
+                            product: info.product,
+                            rel: rel.clone(),
+                            abs: match transforms.get_mut(client_id) {
+                                Some(transform) => transform.abs(id, &info, &abs),
+                                None => abs.clone(),
+                            },
+                            keys: transforms.get(client_id).unwrap_or(&identity).keys(&keys),
+                            leds: device_leds.clone(),
+                            ff: ff.clone(),
+                            raw: raw.clone(),
+                            delay: repeat.delay,
+                            period: repeat.period,
+                            server_repeat,
+                        };
+
+                        let _ = sender.send(update).await;
                     }
                 }
 
                 let (interceptor_sender, mut interceptor_receiver) = mpsc::channel(32);
+                let (command_sender, mut command_receiver) = mpsc::channel(32);
                 devices.insert(Device {
-                    name,
+                    info,
                     version,
-                    vendor,
-                    product,
                     rel,
                     abs,
                     keys,
+                    leds: device_leds,
+                    ff,
+                    raw,
                     delay: repeat.delay,
                     period: repeat.period,
                     sender: interceptor_sender,
+                    commands: command_sender,
+                    serial: next_serial,
                 });
+                next_serial += 1;
 
                 let events_sender = events_sender.clone();
                 tokio::spawn(async move {
+                    let mut output = Output::default();
+
                     loop {
                         tokio::select! {
                             event = interceptor.read() => {
@@ -231,6 +327,17 @@ pub async fn run(
 
                                 tracing::trace!(id = %id, "Wrote an event to device");
                             }
+                            command = command_receiver.recv() => {
+                                let command = match command {
+                                    Some(command) => command,
+                                    None => break,
+                                };
+
+                                // Feedback is best effort, the device keeps working without it.
+                                if let Err(err) = output.apply(&mut interceptor, command) {
+                                    tracing::warn!(id = %id, "Error writing feedback to device: {}", err);
+                                }
+                            }
                         }
                     }
                 });
@@ -239,139 +346,486 @@ pub async fn run(
 
                 tracing::info!(
                     id = %id,
-                    name = ?device.name,
-                    vendor = %device.vendor,
-                    product = %device.product,
+                    name = ?device.info.name,
+                    vendor = %device.info.vendor,
+                    product = %device.info.product,
                     version = %device.version,
+                    pinned = ?pinned,
                     "Registered new device"
                 );
+
+                continue;
             }
             (id, result) = event => match result {
-                Ok(event) => {
-                    let mut press = false;
+                Ok(event) => (id, router.route(id, event)),
+                Err(err) if err.kind() == ErrorKind::BrokenPipe => {
+                    destroy_device(&clients, &router, id).await;
+                    devices.remove(id);
+                    router.unregister_device(id);
+                    leds.retain(|(_, device), _| *device != id);
 
-                    if let Event::Key(KeyEvent { key, down }) = event {
-                        if all_switch_keys.contains(&key) {
-                            press = true;
+                    tracing::info!(id = %id, "Destroyed device");
+                    continue;
+                }
+                Err(err) => return Err(Error::Input(err)),
+            },
+            ((id, serial), event) = macro_event => {
+                // The device might have been removed, with its ID reused by another one since.
+                if devices.get(id).is_none_or(|device| device.serial != serial) {
+                    continue;
+                }
 
-                            match down {
-                                true => pressed_keys.insert(key),
-                                false => pressed_keys.remove(&key),
-                            };
-                        }
+                (id, router.inject(id, event))
+            }
+            // Only synthetic events, no device ID needed.
+            () = idle => (0, router.idle()),
+            result = control_accept => {
+                let (stream, _) = result.map_err(Error::Control)?;
+                let control_sender = control_sender.clone();
+
+                tokio::spawn(async move {
+                    if let Err(err) = control::handle(stream, control_sender).await {
+                        tracing::warn!("Control connection error: {}", err);
                     }
+                });
 
-                    // Who to send this event to.
-                    let mut idx = current;
+                continue;
+            }
+            (request, reply) = control_command => {
+                let (response, route) = command(request, &mut router, &clients, clients_config, &devices);
+                let _ = reply.send(response);
+
+                match route {
+                    // Only synthetic events, no device ID needed.
+                    Some(route) => (0, route),
+                    None => continue,
+                }
+            }
+        };
 
-                    if press {
-                        let exists = |idx| idx == 0 || clients.get(idx - 1).is_some_and(Option::is_some);
+        for change in route.changes {
+            match change {
+                Change::Macro { idx, id } => {
+                    tracing::info!(idx = %idx, id = %id, "Running macro");
 
-                        // we change in previous event keyup should be send to previous
-                        if changed {
-                            idx = previous;
+                    let serial = devices[id].serial;
+                    let r#macro = macros[idx].clone();
+                    let macro_sender = macro_sender.clone();
+                    tokio::spawn(async move { r#macro.run((id, serial), macro_sender).await });
+                }
+                change => apply(change, &clients, &hooks, &devices, &leds, &router).await,
+            }
+        }
 
-                            if pressed_keys.is_empty() {
-                                changed = false
-                            }
-                        } else {
-                            for (keys,&i) in &goto_keys {
-                                if exists(i) && keys.iter().all(|k| pressed_keys.contains(k)) {
-                                    current = i;
-                                    changed = true;
-                                    break;
-                                }
-                            }
+        let writes = route
+            .targets
+            .iter()
+            .flat_map(|idx| route.events.iter().map(move |event| (*idx, id, *event)))
+            .chain(
+                route
+                    .synthetic
+                    .iter()
+                    .map(|synthetic| (synthetic.target, synthetic.id, synthetic.event)),
+            );
+
+        let mut closed = false;
+        for (idx, id, event) in writes {
+            // Index 0 - the server itself.
+            if idx == 0 {
+                // We do a try_send() here rather than a "blocking" send in order to prevent deadlocks.
+                // In this scenario, the interceptor task is sending events to the main task,
+                // while the main task is simultaneously sending events back to the interceptor.
+                // This creates a classic deadlock situation where both tasks are waiting for each other.
+                match devices[id].sender.try_send(event) {
+                    Ok(()) | Err(TrySendError::Closed(_)) => {}
+                    Err(TrySendError::Full(_)) => return Err(Error::Overflow),
+                }
 
-                            if !changed && switch_keys.is_subset(&pressed_keys) {
-                                loop {
-                                    current = (current + 1) % (clients.len() + 1);
-                                    if exists(current) {
-                                        break;
-                                    }
-                                }
+                continue;
+            }
 
-                                changed = true;
-                            }
-                            if changed {
-                                previous = idx;
-                                if current != 0 {
-                                    tracing::info!(idx = %current, addr = %clients[current - 1].as_ref().map_or_else(|| &ADDR_UNKNOWN, |(_,a)| a), "Switched client");
-                                } else {
-                                    tracing::info!(idx = %current, "Switched client");
-                                }
-                            }
-                        }
-                    }
+            // Clients repeat keys on their own unless told otherwise.
+            if !server_repeat && matches!(event, Event::Repeat(_)) {
+                continue;
+            }
 
-                    if press && !propagate_switch_keys {
-                        continue;
-                    }
+            if let Some(Some((s, _, _))) = clients.get(idx - 1) {
+                let event = match transforms.get_mut(idx - 1) {
+                    Some(transform) => transform.event(id, event),
+                    None => Some(event),
+                };
 
-                    let events = [event]
-                        .into_iter()
-                        .chain(press.then_some(Event::Sync(SyncEvent::All)));
-
-                    // Index 0 - special case to keep the modular arithmetic above working.
-                    if idx == 0 {
-                        // We do a try_send() here rather than a "blocking" send in order to prevent deadlocks.
-                        // In this scenario, the interceptor task is sending events to the main task,
-                        // while the main task is simultaneously sending events back to the interceptor.
-                        // This creates a classic deadlock situation where both tasks are waiting for each other.
-                        for event in events {
-                            match devices[id].sender.try_send(event) {
-                                Ok(()) | Err(TrySendError::Closed(_)) => {},
-                                Err(TrySendError::Full(_)) => return Err(Error::Overflow),
-                            }
-                        }
+                // Blocked for this client.
+                let event = match event {
+                    Some(event) => event,
+                    None => continue,
+                };
 
-                        continue;
-                    }
+                closed |= s.send(Update::Event { id, event }).await.is_err();
+            }
+        }
 
-                    for event in events {
-                        if let Some((s,_)) = &clients[idx -1] {
-                            if s.send(Update::Event { id, event }).await.is_err() {
-                                if idx - 1 < static_client.len() {
-                                    clients[idx -1] = None
-                                } else {
-                                    clients.remove(idx - 1);
-                                }
+        if closed {
+            remove_closed(
+                &mut clients,
+                clients_config.len(),
+                &mut router,
+                &hooks,
+                &devices,
+                &mut leds,
+            )
+            .await;
+        }
+    }
+}
 
-                                if current == idx {
-                                    current = 0;
-                                }
-                            }
-                        }
-                    }
-                }
-                Err(err) if err.kind() == ErrorKind::BrokenPipe => {
-                    for (_, e) in &clients {
-                        let _ = match e {
-                            Some((sender,_)) => sender.send(Update::DestroyDevice { id }).await,
-                            None => Ok(()),
-                        };
-                    }
-                    devices.remove(id);
+fn target(clients: &Clients, idx: usize) -> hooks::Target<'_> {
+    // Index 0 - the server itself.
+    let client = idx
+        .checked_sub(1)
+        .and_then(|idx| clients.get(idx))
+        .and_then(Option::as_ref)
+        .map(|(_, addr, name)| (name.as_str(), *addr));
 
-                    tracing::info!(id = %id, "Destroyed device");
-                }
-                Err(err) => return Err(Error::Input(err)),
+    hooks::Target { idx, client }
+}
+
+// Whether a client gets the device, devices pinned to other clients are not created on it at all.
+fn created(router: &Router, id: usize, idx: usize) -> bool {
+    router.pinned(id).is_none_or(|target| target == idx)
+}
+
+// Tells the clients that got the device it's gone, others don't know about it.
+async fn destroy_device(clients: &Clients, router: &Router, id: usize) {
+    for (idx, e) in clients {
+        if let Some((sender, _, _)) = e.as_ref().filter(|_| created(router, id, idx + 1)) {
+            let _ = sender.send(Update::DestroyDevice { id }).await;
+        }
+    }
+}
+
+// Tells a client whether it's the current target, does nothing for the server.
+async fn focus(clients: &Clients, idx: usize, active: bool) {
+    if let Some(Some((sender, _, _))) = idx.checked_sub(1).and_then(|idx| clients.get(idx)) {
+        // A closed connection is noticed and cleaned up elsewhere.
+        let _ = sender.send(Update::Focus { active }).await;
+    }
+}
+
+// Acts on a change the router made, whether it came from routing an event or from a client disconnecting.
+async fn apply(
+    change: Change,
+    clients: &Clients,
+    hooks: &Hooks,
+    devices: &Slab<Device>,
+    leds: &HashMap<(usize, usize), HashMap<Led, bool>>,
+    router: &Router,
+) {
+    match change {
+        Change::Switched {
+            previous,
+            current,
+            reason,
+        } => {
+            switched(clients, hooks, previous, current, reason);
+
+            if previous != current {
+                focus(clients, previous, false).await;
+                focus(clients, current, true).await;
+                switch_devices(devices, leds, router, previous, current);
             }
         }
+        Change::BroadcastStarted { group } => {
+            tracing::info!(group = %group, "Started broadcasting")
+        }
+        Change::BroadcastStopped { group } => {
+            tracing::info!(group = %group, "Stopped broadcasting")
+        }
+        // Macros are only started by routed events, which run them on their own.
+        Change::Macro { .. } => {}
+    }
+}
+
+fn switched(clients: &Clients, hooks: &Hooks, previous: usize, current: usize, reason: Reason) {
+    let next = target(clients, current);
+    match next.client {
+        Some((name, addr)) => {
+            tracing::info!(idx = %current, name = %name, addr = %addr, reason = ?reason, "Switched client")
+        }
+        None => tracing::info!(idx = %current, reason = ?reason, "Switched client"),
+    }
+
+    // Switching to the current target is still logged, but it's not a switch as far as hooks are concerned.
+    if previous != current {
+        hooks.switch(target(clients, previous), next, reason);
+    }
+}
+
+// Shows the LED state the new target last reported, or restores the devices' own one,
+// and stops effects the previous target was playing.
+fn switch_devices(
+    devices: &Slab<Device>,
+    leds: &HashMap<(usize, usize), HashMap<Led, bool>>,
+    router: &Router,
+    previous: usize,
+    current: usize,
+) {
+    for (id, device) in devices {
+        let command = match leds.get(&(current, id)) {
+            Some(state) if current != 0 => Command::ShowLeds(state.clone()),
+            _ => Command::RestoreLeds,
+        };
+
+        let _ = device.commands.try_send(command);
+
+        // A client the device is pinned to still gets its events, so let it keep playing.
+        if previous != 0 && router.pinned(id) != Some(previous) {
+            let _ = device
+                .commands
+                .try_send(Command::StopFf { target: previous });
+        }
+    }
+}
+
+// Gives a connecting client the entry of its configured client, or a new one if it's not configured or already connected.
+fn assign(
+    clients: &mut Clients,
+    transforms: &mut [Transform],
+    clients_config: &[ClientConfig],
+    sender: Sender<Update>,
+    addr: SocketAddr,
+    name: String,
+) -> usize {
+    match config::find_client(clients_config, &name, addr.ip()) {
+        Some(idx) if clients[idx].is_some() => {
+            tracing::warn!("client {} ({}) already connected", name, addr);
+            clients.insert(Some((sender, addr, name)))
+        }
+        Some(idx) => {
+            clients[idx] = Some((sender, addr, name));
+            // Start without keys pressed from the previous connection.
+            transforms[idx] = Transform::new(&clients_config[idx]);
+            idx
+        }
+        None => clients.insert(Some((sender, addr, name))),
+    }
+}
+
+// Removes clients whose connection task has ended, entries of clients from the config are only cleared.
+async fn remove_closed(
+    clients: &mut Clients,
+    configured: usize,
+    router: &mut Router,
+    hooks: &Hooks,
+    devices: &Slab<Device>,
+    leds: &mut HashMap<(usize, usize), HashMap<Led, bool>>,
+) {
+    let closed = clients
+        .iter()
+        .filter(|(_, e)| e.as_ref().is_some_and(|(sender, _, _)| sender.is_closed()))
+        .map(|(idx, _)| idx)
+        .collect::<Vec<_>>();
+
+    for idx in closed {
+        hooks.disconnect(target(clients, idx + 1));
+
+        for change in router.disconnect(idx + 1) {
+            apply(change, clients, hooks, devices, leds, router).await;
+        }
+
+        leds.retain(|(target, _), _| *target != idx + 1);
+
+        for (_, device) in devices {
+            let _ = device
+                .commands
+                .try_send(Command::EraseFf { target: idx + 1 });
+        }
+
+        if idx < configured {
+            clients[idx] = None;
+        } else {
+            clients.remove(idx);
+        }
+    }
+}
+
+fn command(
+    request: Request,
+    router: &mut Router,
+    clients: &Clients,
+    clients_config: &[ClientConfig],
+    devices: &Slab<Device>,
+) -> (Response, Option<Route>) {
+    let target = match request {
+        Request::Switch(Target::Server) => 0,
+        Request::Switch(Target::Index(idx)) => idx,
+        Request::Switch(Target::Name(name)) => {
+            match clients_config
+                .iter()
+                .position(|client| client.name.as_ref() == Some(&name))
+            {
+                Some(idx) => idx + 1,
+                None => return (Response::Error(format!("Unknown client {:?}", name)), None),
+            }
+        }
+        Request::Cycle => return (Response::Done, Some(router.cycle())),
+        Request::Status => {
+            let clients = clients
+                .iter()
+                .map(|(idx, e)| ClientStatus {
+                    idx: idx + 1,
+                    name: match e {
+                        Some((_, _, name)) => Some(name.clone()),
+                        None => clients_config
+                            .get(idx)
+                            .and_then(|client| client.name.clone()),
+                    },
+                    addr: e
+                        .as_ref()
+                        .filter(|(sender, _, _)| !sender.is_closed())
+                        .map(|(_, addr, _)| *addr),
+                })
+                .collect();
+
+            let devices = devices
+                .iter()
+                .map(|(id, device)| DeviceStatus {
+                    id,
+                    name: device.info.name.clone(),
+                    vendor: device.info.vendor,
+                    product: device.info.product,
+                    pinned: router.pinned(id),
+                })
+                .collect();
+
+            let status = Status {
+                current: router.current(),
+                clients,
+                devices,
+            };
+
+            return (Response::Status(status), None);
+        }
+    };
+
+    match router.goto(target) {
+        Some(route) => (Response::Done, Some(route)),
+        None => (
+            Response::Error(format!("Client {} is not connected", target)),
+            None,
+        ),
     }
 }
 
 struct Device {
-    name: CString,
-    vendor: u16,
-    product: u16,
+    info: DeviceInfo,
     version: u16,
     rel: HashSet<RelAxis>,
     abs: HashMap<AbsAxis, AbsInfo>,
     keys: HashSet<Key>,
+    leds: HashSet<Led>,
+    ff: HashSet<Ff>,
+    raw: HashSet<(u16, u16)>,
     delay: Option<i32>,
     period: Option<i32>,
     sender: Sender<Event>,
+    commands: Sender<Command>,
+    // Unlike the ID, never reused for another device.
+    serial: u64,
+}
+
+// Feedback from clients, written to a device by its task.
+enum Command {
+    /// Sets LEDs to the state of a client, saving the device's own state first.
+    ShowLeds(HashMap<Led, bool>),
+    /// Goes back to the saved state.
+    RestoreLeds,
+    /// A force feedback request of a target, with effect IDs as seen by the target.
+    Ff { target: usize, event: FfEvent },
+    /// Stops all effects of a target.
+    StopFf { target: usize },
+    /// Erases all effects of a target.
+    EraseFf { target: usize },
+}
+
+// State of a device task needed to apply commands.
+#[derive(Default)]
+struct Output {
+    // The device's own LED state, saved while showing the state of a client.
+    saved_leds: Option<HashMap<Led, bool>>,
+    // IDs of effects on the device, by target and the effect's ID on the target.
+    effects: HashMap<(usize, i16), i16>,
+}
+
+impl Output {
+    fn apply<T: InterceptorPlatform>(
+        &mut self,
+        interceptor: &mut T,
+        command: Command,
+    ) -> Result<(), io::Error> {
+        match command {
+            Command::ShowLeds(state) => {
+                self.saved_leds.get_or_insert_with(|| interceptor.leds());
+                set_leds(interceptor, state)
+            }
+            Command::RestoreLeds => match self.saved_leds.take() {
+                Some(state) => set_leds(interceptor, state),
+                None => Ok(()),
+            },
+            Command::Ff { target, event } => match event {
+                FfEvent::Upload(effect) => {
+                    // Updating an effect keeps its ID on the device.
+                    let key = (target, effect.id);
+                    let id = self.effects.get(&key).copied().unwrap_or(-1);
+                    let id = interceptor.upload_ff(FfEffect { id, ..effect })?;
+
+                    self.effects.insert(key, id);
+                    Ok(())
+                }
+                FfEvent::Erase { id } => match self.effects.remove(&(target, id)) {
+                    Some(id) => interceptor.erase_ff(id),
+                    None => Ok(()),
+                },
+                FfEvent::Play { id, count } => match self.effects.get(&(target, id)) {
+                    Some(id) => interceptor.play_ff(*id, count),
+                    None => Ok(()),
+                },
+                FfEvent::Gain(gain) => interceptor.set_ff_gain(gain),
+                FfEvent::Autocenter(autocenter) => interceptor.set_ff_autocenter(autocenter),
+            },
+            Command::StopFf { target } => self
+                .effects
+                .iter()
+                .filter(|((effect_target, _), _)| *effect_target == target)
+                .try_for_each(|(_, id)| interceptor.play_ff(*id, 0)),
+            Command::EraseFf { target } => {
+                let ids = self
+                    .effects
+                    .iter()
+                    .filter(|((effect_target, _), _)| *effect_target == target)
+                    .map(|(key, id)| (*key, *id))
+                    .collect::<Vec<_>>();
+
+                for (key, id) in ids {
+                    self.effects.remove(&key);
+                    interceptor.erase_ff(id)?;
+                }
+
+                Ok(())
+            }
+        }
+    }
+}
+
+fn set_leds<T: InterceptorPlatform>(
+    interceptor: &mut T,
+    state: HashMap<Led, bool>,
+) -> Result<(), io::Error> {
+    for (led, on) in state {
+        interceptor.set_led(LedEvent { led, on })?;
+    }
+
+    Ok(())
 }
 
 #[derive(Error, Debug)]
@@ -382,16 +836,27 @@ enum ClientError {
     Version { server: Version, client: Version },
     #[error("Invalid password")]
     Auth,
+    #[error("Client not allowed")]
+    NotAllowed,
     #[error(transparent)]
     Rand(#[from] rand::Error),
 }
 
+// Sent by a connection task once the client is authenticated, the main loop replies
+// with the client's index, updates to send first and a receiver of further updates.
+struct Handshake {
+    name: String,
+    addr: SocketAddr,
+    reply: oneshot::Sender<(usize, VecDeque<Update>, Receiver<Update>)>,
+}
+
 async fn client(
-    mut init_updates: VecDeque<Update>,
-    mut receiver: Receiver<Update>,
     stream: TcpStream,
+    addr: SocketAddr,
     acceptor: TlsAcceptor,
-    password: &str,
+    mut credentials: watch::Receiver<Arc<Credentials>>,
+    handshake_sender: Sender<Handshake>,
+    feedback_sender: Sender<(usize, Feedback)>,
 ) -> Result<(), ClientError> {
     let stream = rkvm_net::timeout(rkvm_net::TLS_TIMEOUT, acceptor.accept(stream)).await?;
     tracing::info!("TLS connected");
@@ -414,6 +879,16 @@ async fn client(
         });
     }
 
+    let Hello { name } =
+        rkvm_net::timeout(rkvm_net::READ_TIMEOUT, Hello::decode(&mut stream)).await?;
+    Span::current().record("name", name.as_str());
+
+    // Still go through the challenge if the client is not allowed, it's told it failed afterwards.
+    let password = credentials
+        .borrow_and_update()
+        .password(&name, addr.ip())
+        .map(ToOwned::to_owned);
+
     let challenge = AuthChallenge::generate().await?;
 
     rkvm_net::timeout(rkvm_net::WRITE_TIMEOUT, async {
@@ -426,9 +901,9 @@ async fn client(
 
     let response =
         rkvm_net::timeout(rkvm_net::READ_TIMEOUT, AuthResponse::decode(&mut stream)).await?;
-    let status = match response.verify(&challenge, password) {
-        true => AuthStatus::Passed,
-        false => AuthStatus::Failed,
+    let status = match &password {
+        Some(password) if response.verify(&challenge, password) => AuthStatus::Passed,
+        _ => AuthStatus::Failed,
     };
 
     rkvm_net::timeout(rkvm_net::WRITE_TIMEOUT, async {
@@ -439,12 +914,39 @@ async fn client(
     })
     .await?;
 
-    if status == AuthStatus::Failed {
-        return Err(ClientError::Auth);
-    }
+    let password = match password {
+        Some(password) if status == AuthStatus::Passed => password,
+        Some(_) => return Err(ClientError::Auth),
+        None => return Err(ClientError::NotAllowed),
+    };
 
     tracing::info!("Authenticated successfully");
 
+    let (reply, response) = oneshot::channel();
+    let handshake = Handshake {
+        name: name.clone(),
+        addr,
+        reply,
+    };
+
+    // The server is shutting down.
+    if handshake_sender.send(handshake).await.is_err() {
+        return Ok(());
+    }
+
+    let (idx, mut init_updates, mut receiver) = match response.await {
+        Ok(response) => response,
+        Err(_) => return Ok(()),
+    };
+
+    Span::current().record("idx", idx);
+    tracing::info!("Registered client");
+
+    // Reading is done separately, feedback can arrive at any time rather than only after pings.
+    let (reader, mut stream) = tokio::io::split(stream);
+    let mut read = Box::pin(rkvm_net::decode_owned::<Feedback, _>(reader));
+    let mut ping: Option<Instant> = None;
+
     let mut interval = time::interval(rkvm_net::PING_INTERVAL);
 
     loop {
@@ -455,12 +957,47 @@ async fn client(
             }
         };
 
+        let timeout = async {
+            match ping {
+                Some(start) => time::sleep_until((start + rkvm_net::READ_TIMEOUT).into()).await,
+                None => future::pending().await,
+            }
+        };
+
         let update = tokio::select! {
             // Make sure pings have priority.
             // The client could time out otherwise.
             biased;
 
             _ = interval.tick() => Some(Update::Ping),
+            (reader, feedback) = &mut read => {
+                read.set(rkvm_net::decode_owned(reader));
+
+                match feedback? {
+                    Feedback::Pong => match ping.take() {
+                        Some(start) => tracing::debug!(duration = ?start.elapsed(), "Received pong"),
+                        None => tracing::warn!("Received unexpected pong"),
+                    },
+                    feedback => {
+                        // The server is shutting down.
+                        if feedback_sender.send((idx + 1, feedback)).await.is_err() {
+                            return Ok(());
+                        }
+                    }
+                }
+
+                continue;
+            }
+            () = timeout => return Err(io::Error::new(ErrorKind::TimedOut, "Pong timed out").into()),
+            // The config was reloaded, disconnect if the client's password changed or it was revoked.
+            Ok(()) = credentials.changed() => {
+                let current = credentials.borrow_and_update().password(&name, addr.ip()) == Some(password.as_str());
+                if !current {
+                    return Err(ClientError::NotAllowed);
+                }
+
+                continue;
+            }
             recv = recv => recv,
         };
 
@@ -483,11 +1020,8 @@ async fn client(
             // Keeping these as debug because it's not as frequent as other updates.
             tracing::debug!(duration = ?duration, "Sent ping");
 
-            let start = Instant::now();
-            rkvm_net::timeout(rkvm_net::READ_TIMEOUT, Pong::decode(&mut stream)).await?;
-            let duration = start.elapsed();
-
-            tracing::debug!(duration = ?duration, "Received pong");
+            // Only the first unanswered ping counts towards the timeout.
+            ping.get_or_insert(Instant::now());
         }
 
         tracing::trace!("Wrote an update");
@@ -495,3 +1029,440 @@ async fn client(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rkvm_input::ff::{FfKind, FfReplay, FfTrigger};
+    use rkvm_input::interceptor::Repeat;
+    use rkvm_input::key::{KeyEvent, Keyboard};
+    use std::ffi::CStr;
+
+    // Stands in for a device, keeping the state set on it.
+    #[derive(Default)]
+    struct Interceptor {
+        info: DeviceInfo,
+        leds: HashMap<Led, bool>,
+        effects: HashMap<i16, FfEffect>,
+        played: Vec<(i16, i32)>,
+    }
+
+    impl InterceptorPlatform for Interceptor {
+        async fn read(&mut self) -> Result<Event, io::Error> {
+            Err(ErrorKind::Unsupported.into())
+        }
+        async fn write(&mut self, _event: &Event) -> Result<(), io::Error> {
+            Err(ErrorKind::Unsupported.into())
+        }
+        fn name(&self) -> &CStr {
+            &self.info.name
+        }
+        fn vendor(&self) -> u16 {
+            self.info.vendor
+        }
+        fn product(&self) -> u16 {
+            self.info.product
+        }
+        fn version(&self) -> u16 {
+            0
+        }
+        fn info(&self) -> &DeviceInfo {
+            &self.info
+        }
+        fn rel(&self) -> HashSet<RelAxis> {
+            HashSet::new()
+        }
+        fn abs(&self) -> HashMap<AbsAxis, AbsInfo> {
+            HashMap::new()
+        }
+        fn key(&self) -> HashSet<Key> {
+            HashSet::new()
+        }
+        fn repeat(&self) -> Repeat {
+            Repeat {
+                delay: None,
+                period: None,
+            }
+        }
+        fn raw(&self) -> HashSet<(u16, u16)> {
+            HashSet::new()
+        }
+        fn leds(&self) -> HashMap<Led, bool> {
+            self.leds.clone()
+        }
+        fn set_led(&mut self, event: LedEvent) -> Result<(), io::Error> {
+            self.leds.insert(event.led, event.on);
+            Ok(())
+        }
+        fn ff(&self) -> HashSet<Ff> {
+            HashSet::new()
+        }
+        fn upload_ff(&mut self, effect: FfEffect) -> Result<i16, io::Error> {
+            // Like the kernel, new effects get the lowest free ID.
+            let id = match effect.id {
+                -1 => (0..).find(|id| !self.effects.contains_key(id)).unwrap(),
+                id => id,
+            };
+
+            self.effects.insert(id, FfEffect { id, ..effect });
+            Ok(id)
+        }
+        fn erase_ff(&mut self, id: i16) -> Result<(), io::Error> {
+            self.effects
+                .remove(&id)
+                .map(|_| ())
+                .ok_or_else(|| ErrorKind::InvalidInput.into())
+        }
+        fn play_ff(&mut self, id: i16, count: i32) -> Result<(), io::Error> {
+            self.played.push((id, count));
+            Ok(())
+        }
+        fn set_ff_gain(&mut self, _gain: u16) -> Result<(), io::Error> {
+            Err(ErrorKind::Unsupported.into())
+        }
+        fn set_ff_autocenter(&mut self, _autocenter: u16) -> Result<(), io::Error> {
+            Err(ErrorKind::Unsupported.into())
+        }
+    }
+
+    fn device(commands: Sender<Command>) -> Device {
+        let (sender, _) = mpsc::channel(1);
+
+        Device {
+            info: DeviceInfo::default(),
+            version: 0,
+            rel: HashSet::new(),
+            abs: HashMap::new(),
+            keys: HashSet::new(),
+            leds: [Led::NumLock, Led::CapsLock].into(),
+            ff: HashSet::new(),
+            raw: HashSet::new(),
+            delay: None,
+            period: None,
+            sender,
+            commands,
+            serial: 0,
+        }
+    }
+
+    fn router(extra: &str) -> Router {
+        let config = format!(
+            r#"
+            listen = "0.0.0.0:5258"
+            switch-keys = ["left-alt", "left-ctrl"]
+            certificate = "/etc/rkvm/certificate.pem"
+            key = "/etc/rkvm/key.pem"
+            password = "123456789"
+
+            [[clients]]
+            name = "laptop"
+
+            [[clients]]
+            name = "desktop"
+            {}
+            "#,
+            extra
+        );
+        let config = toml::from_str::<Config>(&config).unwrap();
+
+        Router::new(&config).unwrap()
+    }
+
+    #[test]
+    fn leds_follow_focus() {
+        let router = router("");
+        let (commands, mut receiver) = mpsc::channel(16);
+        let mut devices = Slab::new();
+        let id = devices.insert(device(commands));
+
+        let mut interceptor = Interceptor {
+            leds: [(Led::NumLock, true), (Led::CapsLock, false)].into(),
+            ..Default::default()
+        };
+        let own = interceptor.leds.clone();
+        let laptop = HashMap::from([(Led::NumLock, false), (Led::CapsLock, true)]);
+        let desktop = HashMap::from([(Led::NumLock, true), (Led::CapsLock, true)]);
+
+        let mut leds = HashMap::new();
+        leds.insert((1, id), laptop.clone());
+        leds.insert((2, id), desktop.clone());
+
+        let mut output = Output::default();
+        let mut switch = |interceptor: &mut Interceptor, leds: &_, previous, current| {
+            switch_devices(&devices, leds, &router, previous, current);
+            while let Ok(command) = receiver.try_recv() {
+                output.apply(interceptor, command).unwrap();
+            }
+        };
+
+        switch(&mut interceptor, &leds, 0, 1);
+        assert_eq!(interceptor.leds, laptop);
+
+        // The device's own state is kept when going from one client to another.
+        switch(&mut interceptor, &leds, 1, 2);
+        assert_eq!(interceptor.leds, desktop);
+
+        switch(&mut interceptor, &leds, 2, 0);
+        assert_eq!(interceptor.leds, own);
+
+        // Clients that haven't reported their state yet see the device's own.
+        leds.clear();
+        switch(&mut interceptor, &leds, 0, 1);
+        assert_eq!(interceptor.leds, own);
+    }
+
+    #[test]
+    fn clients_get_their_configured_entry() {
+        let clients_config = [r#"name = "laptop""#, r#"addr = "10.0.0.1""#]
+            .map(|client| toml::from_str::<ClientConfig>(client).unwrap());
+        let mut transforms = clients_config
+            .iter()
+            .map(Transform::new)
+            .collect::<Vec<_>>();
+
+        let mut clients = Clients::new();
+        for _ in &clients_config {
+            clients.insert(None);
+        }
+
+        let mut assign = |clients: &mut Clients, name: &str, addr: &str| {
+            let (sender, _) = mpsc::channel(1);
+            assign(
+                clients,
+                &mut transforms,
+                &clients_config,
+                sender,
+                addr.parse().unwrap(),
+                name.to_owned(),
+            )
+        };
+
+        assert_eq!(assign(&mut clients, "laptop", "10.0.0.2:5258"), 0);
+        assert_eq!(assign(&mut clients, "desktop", "10.0.0.1:5258"), 1);
+        assert_eq!(assign(&mut clients, "other", "10.0.0.3:5258"), 2);
+        // A second connection with the name of a connected client is kept apart.
+        assert_eq!(assign(&mut clients, "laptop", "10.0.0.4:5258"), 3);
+
+        clients[0] = None;
+        assert_eq!(assign(&mut clients, "laptop", "10.0.0.4:5258"), 0);
+    }
+
+    #[test]
+    fn reconnecting_client_starts_without_keys_pressed() {
+        let clients_config = [toml::from_str::<ClientConfig>(r#"name = "laptop""#).unwrap()];
+        let mut transforms = clients_config
+            .iter()
+            .map(Transform::new)
+            .collect::<Vec<_>>();
+
+        let mut clients = Clients::new();
+        clients.insert(None);
+
+        let key = Key::Key(Keyboard::A);
+        let down = Event::Key(KeyEvent { key, down: true });
+
+        // Repeats are only passed on for keys the transform saw pressed.
+        assert!(transforms[0].event(0, down).is_some());
+        assert!(transforms[0].event(0, Event::Repeat(key)).is_some());
+
+        let (sender, _) = mpsc::channel(1);
+        let addr = "10.0.0.2:5258".parse().unwrap();
+        let idx = assign(
+            &mut clients,
+            &mut transforms,
+            &clients_config,
+            sender,
+            addr,
+            "laptop".to_owned(),
+        );
+
+        assert_eq!(idx, 0);
+        assert!(transforms[0].event(0, Event::Repeat(key)).is_none());
+    }
+
+    #[test]
+    fn effect_ids_are_kept_apart_per_target() {
+        let mut interceptor = Interceptor::default();
+        let mut output = Output::default();
+
+        let rumble = |id, strong_magnitude| {
+            FfEvent::Upload(FfEffect {
+                id,
+                direction: 0,
+                trigger: FfTrigger {
+                    button: 0,
+                    interval: 0,
+                },
+                replay: FfReplay {
+                    length: 1000,
+                    delay: 0,
+                },
+                kind: FfKind::Rumble {
+                    strong_magnitude,
+                    weak_magnitude: 0,
+                },
+            })
+        };
+        let mut apply =
+            |interceptor: &mut Interceptor, command| output.apply(interceptor, command).unwrap();
+
+        // Both targets use the same ID for their first effect.
+        apply(
+            &mut interceptor,
+            Command::Ff {
+                target: 1,
+                event: rumble(0, 0x1000),
+            },
+        );
+        apply(
+            &mut interceptor,
+            Command::Ff {
+                target: 2,
+                event: rumble(0, 0x2000),
+            },
+        );
+        apply(
+            &mut interceptor,
+            Command::Ff {
+                target: 2,
+                event: rumble(1, 0x3000),
+            },
+        );
+        assert_eq!(interceptor.effects.len(), 3);
+
+        // Updating an effect replaces it instead of uploading another one.
+        apply(
+            &mut interceptor,
+            Command::Ff {
+                target: 1,
+                event: rumble(0, 0x4000),
+            },
+        );
+        assert_eq!(interceptor.effects.len(), 3);
+        assert_eq!(
+            interceptor.effects[&0].kind,
+            FfKind::Rumble {
+                strong_magnitude: 0x4000,
+                weak_magnitude: 0
+            }
+        );
+
+        apply(
+            &mut interceptor,
+            Command::Ff {
+                target: 2,
+                event: FfEvent::Play { id: 0, count: 1 },
+            },
+        );
+        apply(
+            &mut interceptor,
+            Command::Ff {
+                target: 1,
+                event: FfEvent::Play { id: 0, count: 2 },
+            },
+        );
+        // Effects of other targets can't be played.
+        apply(
+            &mut interceptor,
+            Command::Ff {
+                target: 1,
+                event: FfEvent::Play { id: 1, count: 1 },
+            },
+        );
+        assert_eq!(interceptor.played, [(1, 1), (0, 2)]);
+
+        interceptor.played.clear();
+        apply(&mut interceptor, Command::StopFf { target: 1 });
+        assert_eq!(interceptor.played, [(0, 0)]);
+
+        // Erasing an effect of another target does nothing.
+        apply(
+            &mut interceptor,
+            Command::Ff {
+                target: 1,
+                event: FfEvent::Erase { id: 1 },
+            },
+        );
+        assert_eq!(interceptor.effects.len(), 3);
+
+        apply(
+            &mut interceptor,
+            Command::Ff {
+                target: 2,
+                event: FfEvent::Erase { id: 0 },
+            },
+        );
+        assert_eq!(
+            interceptor.effects.keys().collect::<HashSet<_>>(),
+            HashSet::from([&0, &2])
+        );
+
+        // A new effect reuses the freed ID on the device, without mixing it up with the other target's.
+        apply(
+            &mut interceptor,
+            Command::Ff {
+                target: 2,
+                event: rumble(0, 0x5000),
+            },
+        );
+        apply(
+            &mut interceptor,
+            Command::Ff {
+                target: 2,
+                event: FfEvent::Play { id: 0, count: 1 },
+            },
+        );
+        assert_eq!(interceptor.played.last(), Some(&(1, 1)));
+
+        // Disconnecting erases everything a target uploaded.
+        apply(&mut interceptor, Command::EraseFf { target: 2 });
+        assert_eq!(interceptor.effects.keys().collect::<Vec<_>>(), [&0]);
+
+        apply(&mut interceptor, Command::EraseFf { target: 1 });
+        assert!(interceptor.effects.is_empty());
+        assert!(output.effects.is_empty());
+    }
+
+    #[tokio::test]
+    async fn pinned_devices_are_only_destroyed_where_created() {
+        let mut router = router(
+            r#"
+            [[device-routes]]
+            client = "desktop"
+            name = "Tablet"
+            "#,
+        );
+
+        let tablet = DeviceInfo {
+            name: c"Tablet".into(),
+            ..Default::default()
+        };
+        router.register_device(0, &tablet);
+        router.register_device(1, &DeviceInfo::default());
+
+        let mut clients = Clients::new();
+        let mut receivers = Vec::new();
+        for _ in 0..3 {
+            let (sender, receiver) = mpsc::channel(1);
+            clients.insert(Some((
+                sender,
+                "10.0.0.1:5258".parse().unwrap(),
+                String::new(),
+            )));
+            receivers.push(receiver);
+        }
+
+        let mut destroyed = |id| {
+            receivers
+                .iter_mut()
+                .map(|receiver| matches!(receiver.try_recv(), Ok(Update::DestroyDevice { id: other }) if other == id))
+                .collect::<Vec<_>>()
+        };
+
+        destroy_device(&clients, &router, 0).await;
+        assert_eq!(destroyed(0), [false, true, false]);
+
+        destroy_device(&clients, &router, 1).await;
+        assert_eq!(destroyed(1), [true, true, true]);
+    }
+}
@@ -0,0 +1,430 @@
+use crate::config::{AbsTransformConfig, Area, ClientConfig, Rotation};
+use rkvm_input::abs::{AbsAxis, AbsEvent, AbsInfo};
+use rkvm_input::device::{DeviceInfo, DeviceSpec};
+use rkvm_input::event::Event;
+use rkvm_input::key::{Key, KeyEvent};
+use std::collections::{HashMap, HashSet};
+
+// Axes rotated, swapped and inverted together by abs transforms.
+const ABS_PAIRS: [(AbsAxis, AbsAxis); 2] = [
+    (AbsAxis::X, AbsAxis::Y),
+    (AbsAxis::MtPositionX, AbsAxis::MtPositionY),
+];
+
+// The axis each source axis is written to, along with the source range if the value is inverted.
+type AbsAxes = HashMap<AbsAxis, (AbsAxis, Option<(i32, i32)>)>;
+
+/// Changes applied to events before they are sent to a particular client.
+///
+/// Blocked keys and chords apply to keys after remapping.
+#[derive(Default)]
+pub struct Transform {
+    remap: HashMap<Key, Key>,
+    blocked_keys: HashSet<Key>,
+    blocked_chords: Vec<HashSet<Key>>,
+    // Keys pressed on the client.
+    pressed: HashSet<Key>,
+    // Keys whose press completed a blocked chord, their release is dropped too.
+    suppressed: HashSet<Key>,
+    abs_transforms: Vec<AbsTransform>,
+    // Per device with an abs transform.
+    abs_axes: HashMap<usize, AbsAxes>,
+}
+
+impl Transform {
+    pub fn new(config: &ClientConfig) -> Self {
+        let keys = |keys: &HashSet<_>| keys.iter().copied().map(Into::into).collect();
+
+        let remap = config
+            .remap
+            .iter()
+            .map(|(from, to)| ((*from).into(), (*to).into()))
+            .collect();
+
+        Self {
+            remap,
+            blocked_keys: keys(&config.blocked_keys),
+            blocked_chords: config.blocked_chords.iter().map(keys).collect(),
+            pressed: HashSet::new(),
+            suppressed: HashSet::new(),
+            abs_transforms: config
+                .abs_transforms
+                .iter()
+                .map(AbsTransform::new)
+                .collect(),
+            abs_axes: HashMap::new(),
+        }
+    }
+
+    /// Returns the event to send to the client, None if it's blocked.
+    pub fn event(&mut self, id: usize, event: Event) -> Option<Event> {
+        match event {
+            Event::Abs(AbsEvent::Axis { axis, value }) => {
+                match self.abs_axes.get(&id).and_then(|axes| axes.get(&axis)) {
+                    Some(&(axis, range)) => Some(Event::Abs(AbsEvent::Axis {
+                        axis,
+                        value: range.map_or(value, |range| invert(range, value)),
+                    })),
+                    None => Some(event),
+                }
+            }
+            Event::Key(KeyEvent { key, down }) => self.key_event(self.key(key), down),
+            // Repeats of blocked or suppressed keys are dropped along with their presses.
+            Event::Repeat(key) => {
+                let key = self.key(key);
+                self.pressed.contains(&key).then_some(Event::Repeat(key))
+            }
+            event => Some(event),
+        }
+    }
+
+    fn key_event(&mut self, key: Key, down: bool) -> Option<Event> {
+        if self.blocked_keys.contains(&key) {
+            return None;
+        }
+
+        if !down {
+            self.pressed.remove(&key);
+
+            if self.suppressed.remove(&key) {
+                return None;
+            }
+        } else if self.suppressed.contains(&key) {
+            return None;
+        } else {
+            let blocked = self.blocked_chords.iter().any(|chord| {
+                chord.contains(&key)
+                    && chord
+                        .iter()
+                        .all(|other| *other == key || self.pressed.contains(other))
+            });
+
+            if blocked {
+                self.suppressed.insert(key);
+                return None;
+            }
+
+            self.pressed.insert(key);
+        }
+
+        Some(Event::Key(KeyEvent { key, down }))
+    }
+
+    /// Rewrites the keys a device advertises, so that the virtual device can emit the remapped ones
+    /// and doesn't advertise blocked ones.
+    pub fn keys(&self, keys: &HashSet<Key>) -> HashSet<Key> {
+        keys.iter()
+            .map(|key| self.key(*key))
+            .filter(|key| !self.blocked_keys.contains(key))
+            .collect()
+    }
+
+    /// Rewrites the axes a device advertises according to the first matching abs transform,
+    /// remembering how to rewrite the device's events.
+    ///
+    /// Has to be called for every device created on the client.
+    pub fn abs(
+        &mut self,
+        id: usize,
+        device: &DeviceInfo,
+        abs: &HashMap<AbsAxis, AbsInfo>,
+    ) -> HashMap<AbsAxis, AbsInfo> {
+        let transform = self
+            .abs_transforms
+            .iter()
+            .find(|transform| transform.device.matches(device));
+
+        let transform = match transform {
+            Some(transform) => transform,
+            None => {
+                // Device IDs are reused.
+                self.abs_axes.remove(&id);
+                return abs.clone();
+            }
+        };
+
+        let mut infos = abs.clone();
+        let mut axes = HashMap::new();
+
+        for (x, y) in ABS_PAIRS {
+            let (x_info, y_info) = match (abs.get(&x), abs.get(&y)) {
+                (Some(x_info), Some(y_info)) => (*x_info, *y_info),
+                _ => continue,
+            };
+
+            let ((x_source, x_info), (y_source, y_info)) = if transform.swap {
+                ((y, y_info), (x, x_info))
+            } else {
+                ((x, x_info), (y, y_info))
+            };
+
+            let range = |invert: bool, info: AbsInfo| invert.then_some((info.min, info.max));
+            axes.insert(x_source, (x, range(transform.invert_x, x_info)));
+            axes.insert(y_source, (y, range(transform.invert_y, y_info)));
+
+            let (x_info, y_info) = match transform.area {
+                Some(area) => (
+                    extend(x_info, area.left, area.right),
+                    extend(y_info, area.top, area.bottom),
+                ),
+                None => (x_info, y_info),
+            };
+
+            infos.insert(x, x_info);
+            infos.insert(y, y_info);
+        }
+
+        self.abs_axes.insert(id, axes);
+        infos
+    }
+
+    fn key(&self, key: Key) -> Key {
+        self.remap.get(&key).copied().unwrap_or(key)
+    }
+}
+
+struct AbsTransform {
+    device: DeviceSpec,
+    swap: bool,
+    invert_x: bool,
+    invert_y: bool,
+    area: Option<Area>,
+}
+
+impl AbsTransform {
+    fn new(config: &AbsTransformConfig) -> Self {
+        // Rotation is swapping and inverting axes, applied before the rest.
+        let (swap, invert_x, invert_y) = match config.rotate.unwrap_or(Rotation::None) {
+            Rotation::None => (false, false, false),
+            Rotation::Quarter => (true, true, false),
+            Rotation::Half => (false, true, true),
+            Rotation::ThreeQuarters => (true, false, true),
+        };
+
+        let swap_xy = config.swap_xy.unwrap_or(false);
+        let (invert_x, invert_y) = if swap_xy {
+            (invert_y, invert_x)
+        } else {
+            (invert_x, invert_y)
+        };
+
+        Self {
+            device: config.device.clone(),
+            swap: swap ^ swap_xy,
+            invert_x: invert_x ^ config.invert_x.unwrap_or(false),
+            invert_y: invert_y ^ config.invert_y.unwrap_or(false),
+            area: config.area,
+        }
+    }
+}
+
+fn invert((min, max): (i32, i32), value: i32) -> i32 {
+    let value = i64::from(min) + i64::from(max) - i64::from(value);
+    value.clamp(i32::MIN.into(), i32::MAX.into()) as i32
+}
+
+// Extends the range so that the original one covers only the part between low and high.
+fn extend(info: AbsInfo, low: f64, high: f64) -> AbsInfo {
+    let span = (f64::from(info.max) - f64::from(info.min)) / (high - low);
+    let min = f64::from(info.min) - low * span;
+
+    AbsInfo {
+        min: min.round() as i32,
+        max: (min + span).round() as i32,
+        ..info
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rkvm_input::key::{Button, Keyboard};
+    use std::ffi::CStr;
+
+    fn transform() -> Transform {
+        let config = toml::from_str::<ClientConfig>(
+            r#"
+            addr = "10.10.0.1"
+            remap = { caps-lock = "left-ctrl", b-left = "b-right", b-right = "b-left" }
+            "#,
+        )
+        .unwrap();
+
+        Transform::new(&config)
+    }
+
+    #[test]
+    fn remaps_keys() {
+        let mut transform = transform();
+
+        let event = |key, down| Event::Key(KeyEvent { key, down });
+
+        assert_eq!(
+            transform.event(0, event(Key::Key(Keyboard::CapsLock), true)),
+            Some(event(Key::Key(Keyboard::LeftCtrl), true))
+        );
+        assert_eq!(
+            transform.event(0, event(Key::Button(Button::Left), false)),
+            Some(event(Key::Button(Button::Right), false))
+        );
+        assert_eq!(
+            transform.event(0, event(Key::Key(Keyboard::A), true)),
+            Some(event(Key::Key(Keyboard::A), true))
+        );
+
+        assert_eq!(
+            transform.event(0, Event::Repeat(Key::Key(Keyboard::CapsLock))),
+            Some(Event::Repeat(Key::Key(Keyboard::LeftCtrl)))
+        );
+        // Not pressed on the client.
+        assert_eq!(
+            transform.event(0, Event::Repeat(Key::Key(Keyboard::B))),
+            None
+        );
+    }
+
+    #[test]
+    fn remaps_capabilities() {
+        let transform = transform();
+
+        let keys = [
+            Key::Key(Keyboard::CapsLock),
+            Key::Key(Keyboard::A),
+            Key::Button(Button::Left),
+            Key::Button(Button::Right),
+        ];
+
+        let expected = [
+            Key::Key(Keyboard::LeftCtrl),
+            Key::Key(Keyboard::A),
+            Key::Button(Button::Left),
+            Key::Button(Button::Right),
+        ];
+
+        assert_eq!(
+            transform.keys(&keys.into_iter().collect()),
+            expected.into_iter().collect()
+        );
+    }
+
+    #[test]
+    fn blocks_keys_and_chords() {
+        let config = toml::from_str::<ClientConfig>(
+            r#"
+            addr = "10.10.0.1"
+            remap = { f12 = "power" }
+            blocked-keys = ["power", "sleep"]
+            blocked-chords = [["left-ctrl", "left-alt", "backspace"]]
+            "#,
+        )
+        .unwrap();
+
+        let mut transform = Transform::new(&config);
+        let mut event = |key, down| {
+            transform.event(
+                0,
+                Event::Key(KeyEvent {
+                    key: Key::Key(key),
+                    down,
+                }),
+            )
+        };
+
+        assert!(event(Keyboard::Sleep, true).is_none());
+        // Blocked after remapping.
+        assert!(event(Keyboard::F12, true).is_none());
+
+        assert!(event(Keyboard::LeftCtrl, true).is_some());
+        assert!(event(Keyboard::LeftAlt, true).is_some());
+        assert!(event(Keyboard::Backspace, true).is_none());
+        assert!(event(Keyboard::Backspace, false).is_none());
+        assert!(event(Keyboard::LeftAlt, false).is_some());
+
+        // Not a complete chord anymore.
+        assert!(event(Keyboard::Backspace, true).is_some());
+        assert!(event(Keyboard::Backspace, false).is_some());
+
+        assert_eq!(
+            transform.keys(&[Key::Key(Keyboard::F12), Key::Key(Keyboard::A)].into()),
+            [Key::Key(Keyboard::A)].into()
+        );
+    }
+
+    #[test]
+    fn transforms_abs_axes() {
+        let config = toml::from_str::<ClientConfig>(
+            r#"
+            addr = "10.10.0.1"
+            abs-transforms = [{ name = "Tablet", rotate = 90, area = [0.0, 0.0, 0.5, 1.0] }]
+            "#,
+        )
+        .unwrap();
+
+        let info = |max, resolution| AbsInfo {
+            min: 0,
+            max,
+            fuzz: 0,
+            flat: 0,
+            resolution,
+        };
+
+        let abs = [
+            (AbsAxis::X, info(2000, 10)),
+            (AbsAxis::Y, info(1000, 20)),
+            (AbsAxis::Pressure, info(255, 0)),
+        ]
+        .into();
+
+        let mut transform = Transform::new(&config);
+        let device = |name: &CStr, product| DeviceInfo {
+            name: name.into(),
+            vendor: 1,
+            product,
+            ..Default::default()
+        };
+
+        let infos = transform.abs(0, &device(c"Tablet", 1), &abs);
+
+        // Y becomes X, covering the left half of the range.
+        assert_eq!(infos[&AbsAxis::X].max, 2000);
+        assert_eq!(infos[&AbsAxis::X].resolution, 20);
+        assert_eq!(infos[&AbsAxis::Y].max, 2000);
+        assert_eq!(infos[&AbsAxis::Y].resolution, 10);
+        assert_eq!(infos[&AbsAxis::Pressure].max, 255);
+
+        let mut abs = |axis, value| transform.event(0, Event::Abs(AbsEvent::Axis { axis, value }));
+
+        assert_eq!(
+            abs(AbsAxis::Y, 100),
+            Some(Event::Abs(AbsEvent::Axis {
+                axis: AbsAxis::X,
+                value: 900
+            }))
+        );
+        assert_eq!(
+            abs(AbsAxis::X, 100),
+            Some(Event::Abs(AbsEvent::Axis {
+                axis: AbsAxis::Y,
+                value: 100
+            }))
+        );
+
+        // Other devices are left alone.
+        assert!(transform
+            .abs(1, &device(c"Touchpad", 2), &HashMap::new())
+            .is_empty());
+        assert_eq!(
+            transform.event(
+                1,
+                Event::Abs(AbsEvent::Axis {
+                    axis: AbsAxis::X,
+                    value: 100
+                })
+            ),
+            Some(Event::Abs(AbsEvent::Axis {
+                axis: AbsAxis::X,
+                value: 100
+            }))
+        );
+    }
+}